@@ -0,0 +1,184 @@
+use crate::board::{flip_disk, Board, Disk};
+use crate::engine::Side;
+use crate::position::Coordinate;
+use crate::search;
+use rand::rngs::SmallRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::cmp::Ordering;
+
+pub trait MovePicker {
+    fn pick(&mut self, board: &Board, disk: Disk) -> Option<Coordinate>;
+}
+
+// ---------------------------------------------------------------------
+
+pub struct RandomPicker {
+    rng: SmallRng,
+}
+
+impl RandomPicker {
+    pub fn new(seed: u64) -> RandomPicker {
+        RandomPicker {
+            rng: SmallRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl MovePicker for RandomPicker {
+    fn pick(&mut self, board: &Board, disk: Disk) -> Option<Coordinate> {
+        board.valid_moves(disk).choose(&mut self.rng).copied()
+    }
+}
+
+// ---------------------------------------------------------------------
+
+pub struct EnginePicker {
+    depth: u8,
+}
+
+impl EnginePicker {
+    pub fn new(depth: u8) -> EnginePicker {
+        EnginePicker { depth }
+    }
+}
+
+impl MovePicker for EnginePicker {
+    fn pick(&mut self, board: &Board, disk: Disk) -> Option<Coordinate> {
+        search::best_move(board, disk, self.depth)
+    }
+}
+
+// ---------------------------------------------------------------------
+
+/// Plays a game from the usual start, ending early if a picker gives no
+/// move or an illegal one.
+pub fn play_game(
+    black: &mut dyn MovePicker,
+    white: &mut dyn MovePicker,
+) -> Board {
+    let mut board = Board::new();
+    board.init();
+    let mut sides = |board: &Board, side: Side| match side {
+        Side::Dark => black.pick(board, Disk::Black),
+        Side::Light => white.pick(board, Disk::White),
+    };
+
+    board.play_game(&mut sides).0
+}
+
+// ---------------------------------------------------------------------
+
+#[derive(Debug, Default, PartialEq)]
+pub struct MatchResult {
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+pub struct Matchup {
+    pub first: String,
+    pub second: String,
+    pub result: MatchResult,
+}
+
+/// Plays `games` games between the two pickers, alternating colors, and
+/// returns the results from `first`'s point of view.
+pub fn play_match(
+    first: &mut dyn MovePicker,
+    second: &mut dyn MovePicker,
+    games: u32,
+) -> MatchResult {
+    let mut result = MatchResult::default();
+
+    for game in 0..games {
+        let (first_disk, board) = if game % 2 == 0 {
+            (Disk::Black, play_game(first, second))
+        } else {
+            (Disk::White, play_game(second, first))
+        };
+        let mine = board.count(first_disk);
+        let theirs = board.count(flip_disk(&first_disk));
+        match mine.cmp(&theirs) {
+            Ordering::Greater => result.wins += 1,
+            Ordering::Equal => result.draws += 1,
+            Ordering::Less => result.losses += 1,
+        }
+    }
+
+    result
+}
+
+pub fn round_robin(
+    players: &mut [(String, Box<dyn MovePicker>)],
+    games: u32,
+) -> Vec<Matchup> {
+    let mut matchups = Vec::new();
+
+    for i in 0..players.len() {
+        for j in (i + 1)..players.len() {
+            let (head, tail) = players.split_at_mut(j);
+            let (first_name, first) = &mut head[i];
+            let (second_name, second) = &mut tail[0];
+            matchups.push(Matchup {
+                first: first_name.clone(),
+                second: second_name.clone(),
+                result: play_match(first.as_mut(), second.as_mut(), games),
+            });
+        }
+    }
+
+    matchups
+}
+
+// =====================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{play_game, round_robin};
+    use super::{EnginePicker, MovePicker, RandomPicker};
+    use crate::board::Disk;
+
+    #[test]
+    fn play_game_to_the_end() {
+        let mut black = RandomPicker::new(1);
+        let mut white = RandomPicker::new(2);
+        let board = play_game(&mut black, &mut white);
+        assert!(board.valid_moves(Disk::Black).is_empty());
+        assert!(board.valid_moves(Disk::White).is_empty());
+    }
+
+    #[test]
+    fn random_picker_is_reproducible() {
+        let mut black = RandomPicker::new(7);
+        let mut white = RandomPicker::new(8);
+        let first = play_game(&mut black, &mut white);
+
+        let mut black = RandomPicker::new(7);
+        let mut white = RandomPicker::new(8);
+        let second = play_game(&mut black, &mut white);
+        assert_eq!(first.render_compact(), second.render_compact());
+
+        let mut black = RandomPicker::new(9);
+        let mut white = RandomPicker::new(10);
+        let other = play_game(&mut black, &mut white);
+        assert_ne!(first.render_compact(), other.render_compact());
+    }
+
+    #[test]
+    fn round_robin_engine_vs_random() {
+        let mut players: Vec<(String, Box<dyn MovePicker>)> = vec![
+            ("random".to_string(), Box::new(RandomPicker::new(42))),
+            ("depth-1".to_string(), Box::new(EnginePicker::new(1))),
+        ];
+        let matchups = round_robin(&mut players, 2);
+        assert_eq!(matchups.len(), 1);
+
+        let matchup = &matchups[0];
+        assert_eq!(matchup.first, "random");
+        assert_eq!(matchup.second, "depth-1");
+        let result = &matchup.result;
+        assert_eq!(result.wins + result.draws + result.losses, 2);
+        assert!(result.losses >= result.wins);
+    }
+}
@@ -0,0 +1,111 @@
+use crate::board::{Board, Disk};
+use crate::position::{Column, Coordinate, Row};
+use std::env;
+
+pub const RESET: &str = "\x1b[0m";
+pub const BOARD: &str = "\x1b[42m";
+pub const LAST_MOVE: &str = "\x1b[43m";
+pub const BLACK_DISK: &str = "\x1b[90m";
+pub const WHITE_DISK: &str = "\x1b[97m";
+pub const HINT: &str = "\x1b[2m";
+
+/// Colors are used only on a terminal, and never when `NO_COLOR` is set.
+pub fn enabled() -> bool {
+    atty::is(atty::Stream::Stdout) && env::var_os("NO_COLOR").is_none()
+}
+
+/// Draws the board like `Board::render_grid`, but on a green background
+/// with ● disks and the last move highlighted in yellow.
+pub fn render_grid(board: &Board, last_move: Option<Coordinate>) -> String {
+    render_grid_with_hints(board, last_move, &[])
+}
+
+/// Like `render_grid`, but puts a dim dot on the empty squares among
+/// `hints`.
+pub fn render_grid_with_hints(
+    board: &Board,
+    last_move: Option<Coordinate>,
+    hints: &[Coordinate],
+) -> String {
+    let mut output = String::with_capacity(2048);
+    output += "   a  b  c  d  e  f  g  h\n";
+    for row in Row::iter() {
+        output += format!("{} ", row).as_str();
+        for col in Column::iter() {
+            let coord = Coordinate::from_parts(col, row);
+            let disk = board.get_disk(coord);
+            if disk.is_none() && hints.contains(&coord) {
+                output += &format!("{}{} · {}", BOARD, HINT, RESET);
+            } else {
+                output += &cell(disk, last_move == Some(coord));
+            }
+        }
+        output += "\n";
+    }
+
+    output
+}
+
+fn cell(disk: Option<Disk>, highlight: bool) -> String {
+    let background = if highlight { LAST_MOVE } else { BOARD };
+    match disk {
+        None => format!("{}   {}", background, RESET),
+        Some(Disk::Black) => {
+            format!("{}{} ● {}", background, BLACK_DISK, RESET)
+        }
+        Some(Disk::White) => {
+            format!("{}{} ● {}", background, WHITE_DISK, RESET)
+        }
+    }
+}
+
+// =====================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{cell, render_grid};
+    use super::{BLACK_DISK, BOARD, LAST_MOVE, RESET, WHITE_DISK};
+    use crate::board::{Board, Disk};
+    use crate::position::Coordinate;
+
+    #[test]
+    fn ansi_cells() {
+        assert_eq!(cell(None, false), "\x1b[42m   \x1b[0m");
+        assert_eq!(
+            cell(Some(Disk::Black), false),
+            "\x1b[42m\x1b[90m ● \x1b[0m"
+        );
+        assert_eq!(
+            cell(Some(Disk::White), true),
+            "\x1b[43m\x1b[97m ● \x1b[0m"
+        );
+    }
+
+    #[test]
+    fn ansi_render_grid() {
+        let mut board = Board::new();
+        board.init();
+        let f5 = Coordinate::new('f', 5);
+        let board = board.try_move(f5, Disk::Black).unwrap();
+
+        let output = render_grid(&board, Some(f5));
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 9);
+        assert_eq!(lines[0], "   a  b  c  d  e  f  g  h");
+
+        let empty = format!("{}   {}", BOARD, RESET);
+        let black = format!("{}{} ● {}", BOARD, BLACK_DISK, RESET);
+        let white = format!("{}{} ● {}", BOARD, WHITE_DISK, RESET);
+        let last = format!("{}{} ● {}", LAST_MOVE, BLACK_DISK, RESET);
+        let row5 = format!(
+            "5 {}{}{}{}{}{}{}{}",
+            empty, empty, empty, black, black, last, empty, empty
+        );
+        assert_eq!(lines[5], row5);
+        let row4 = format!(
+            "4 {}{}{}{}{}{}{}{}",
+            empty, empty, empty, white, black, empty, empty, empty
+        );
+        assert_eq!(lines[4], row4);
+    }
+}
@@ -1,6 +1,11 @@
-use crate::position::Coordinate;
+use crate::engine::Side;
+use crate::heuristic::{GamePhase, Parity};
+use crate::heuristic::{ENDGAME_MAX_EMPTIES, OPENING_MIN_EMPTIES};
+use crate::position::{Column, Coordinate, Row};
+use std::cmp::{Ordering, Reverse};
 use std::collections::HashMap;
 use std::fmt;
+use std::str::FromStr;
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Disk {
@@ -8,7 +13,28 @@ pub enum Disk {
     White,
 }
 
-fn flip_disk(disk: &Disk) -> Disk {
+impl Disk {
+    pub fn from_char(c: char) -> Option<Disk> {
+        match c {
+            'x' | 'X' | 'b' | 'B' => Some(Disk::Black),
+            'o' | 'O' | 'w' | 'W' => Some(Disk::White),
+            _ => None,
+        }
+    }
+
+    pub fn to_char(self) -> char {
+        match self {
+            Disk::Black => 'x',
+            Disk::White => 'o',
+        }
+    }
+}
+
+fn is_empty_char(c: char) -> bool {
+    c == '.' || c == '-'
+}
+
+pub fn flip_disk(disk: &Disk) -> Disk {
     match disk {
         Disk::Black => Disk::White,
         Disk::White => Disk::Black,
@@ -17,15 +43,61 @@ fn flip_disk(disk: &Disk) -> Disk {
 
 // ---------------------------------------------------------------------
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum MoveErr {
     NotEmpty,
     NoDiskFlipped,
+    OutOfBounds,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ParseBoardErr {
+    InvalidChar(char),
+    WrongLength(usize),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum HexError {
+    WrongLength(usize),
+    InvalidDigit(char),
+    Overlap,
 }
 
 // ---------------------------------------------------------------------
 
-#[derive(Clone)]
+pub const ZOBRIST_BLACK: [[u64; 8]; 8] = zobrist_table(1);
+pub const ZOBRIST_WHITE: [[u64; 8]; 8] = zobrist_table(2);
+pub const ZOBRIST_SIDE: [u64; 2] = [splitmix64(0), splitmix64(1)];
+
+/// Fills a table with pseudo-random keys, indexed by `[row][col]`.
+const fn zobrist_table(seed: u64) -> [[u64; 8]; 8] {
+    let mut table = [[0; 8]; 8];
+    let mut index = 0;
+    while index < 64 {
+        table[index / 8][index % 8] = splitmix64(seed * 64 + index as u64);
+        index += 1;
+    }
+
+    table
+}
+
+const fn splitmix64(state: u64) -> u64 {
+    let mut z = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+// ---------------------------------------------------------------------
+
+/// The disks of a board, saved by `Board::snapshot` so that a front-end
+/// can take a move back with `Board::restore`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoardSnapshot {
+    disks: HashMap<Coordinate, Disk>,
+}
+
+#[derive(Clone, Default, PartialEq)]
 pub struct Board {
     disks: HashMap<Coordinate, Disk>,
     stack: Vec<Coordinate>,
@@ -49,6 +121,17 @@ impl Board {
         self.place(Coordinate::new('e', 5), Disk::White);
     }
 
+    pub fn snapshot(&self) -> BoardSnapshot {
+        BoardSnapshot {
+            disks: self.disks.clone(),
+        }
+    }
+
+    pub fn restore(&mut self, snap: &BoardSnapshot) {
+        self.disks = snap.disks.clone();
+        self.stack.clear();
+    }
+
     pub fn get_disk(&self, coord: Coordinate) -> Option<Disk> {
         match self.disks.get(&coord) {
             None => None,
@@ -56,6 +139,605 @@ impl Board {
         }
     }
 
+    pub fn count(&self, disk: Disk) -> usize {
+        self.disks.values().filter(|&&d| d == disk).count()
+    }
+
+    pub fn empty_count(&self) -> usize {
+        64 - self.count(Disk::Black) - self.count(Disk::White)
+    }
+
+    pub fn parity(&self) -> Parity {
+        match self.empty_count() % 2 {
+            0 => Parity::Even,
+            _ => Parity::Odd,
+        }
+    }
+
+    pub fn phase(&self) -> GamePhase {
+        let empties = self.empty_count();
+        if empties >= OPENING_MIN_EMPTIES {
+            GamePhase::Opening
+        } else if empties > ENDGAME_MAX_EMPTIES {
+            GamePhase::Midgame
+        } else {
+            GamePhase::Endgame
+        }
+    }
+
+    /// Hashes the disks on the board, computed from scratch.
+    pub fn zobrist_hash(&self) -> u64 {
+        let mut hash = 0;
+        for (coord, disk) in self.disks.iter() {
+            let (col, row) = coord.indices();
+            hash ^= match disk {
+                Disk::Black => ZOBRIST_BLACK[row][col],
+                Disk::White => ZOBRIST_WHITE[row][col],
+            };
+        }
+
+        hash
+    }
+
+    /// Like `zobrist_hash`, but also tells apart the side to move.
+    pub fn zobrist_hash_with_side(&self, side: Side) -> u64 {
+        let key = match side {
+            Side::Dark => ZOBRIST_SIDE[0],
+            Side::Light => ZOBRIST_SIDE[1],
+        };
+
+        self.zobrist_hash() ^ key
+    }
+
+    pub fn corners(&self) -> [(Coordinate, Option<Disk>); 4] {
+        let corner = |col, row| {
+            let coord = Coordinate::new(col, row);
+            (coord, self.get_disk(coord))
+        };
+
+        [
+            corner('a', 1),
+            corner('h', 1),
+            corner('a', 8),
+            corner('h', 8),
+        ]
+    }
+
+    pub fn corners_held(&self, disk: Disk) -> usize {
+        self.corners()
+            .iter()
+            .filter(|&&(_, d)| d == Some(disk))
+            .count()
+    }
+
+    /// Counts the empty edge squares between two disks of the opponent of
+    /// `disk` along the edge, where `disk` may wedge in: the opponent can
+    /// then never flip that disk along the edge.
+    pub fn count_wedges(&self, disk: Disk) -> u8 {
+        let opponent = Some(flip_disk(&disk));
+        let mut count = 0;
+        for edge in edges().iter() {
+            for squares in edge.windows(3) {
+                if self.get_disk(squares[0]) == opponent
+                    && self.get_disk(squares[1]).is_none()
+                    && self.get_disk(squares[2]) == opponent
+                {
+                    count += 1;
+                }
+            }
+        }
+
+        count
+    }
+
+    /// Counts the disks of `disk` on the four edges that can't be flipped
+    /// along their edge: runs of them reaching a corner, or with occupied
+    /// squares on both sides. This approximates stability, ignoring flips
+    /// coming from inside the board.
+    pub fn edge_stability_count(&self, disk: Disk) -> u8 {
+        // Corners lie on two edges, so count squares rather than runs.
+        let mut stable = [false; 64];
+        for edge in edges().iter() {
+            let mut start = 0;
+            while start < 8 {
+                if self.get_disk(edge[start]) != Some(disk) {
+                    start += 1;
+                    continue;
+                }
+                let mut end = start;
+                while end < 8 && self.get_disk(edge[end]) == Some(disk) {
+                    end += 1;
+                }
+                let anchored = start == 0 || end == 8;
+                let bounded = anchored
+                    || (self.get_disk(edge[start - 1]).is_some()
+                        && self.get_disk(edge[end]).is_some());
+                if bounded {
+                    for &coord in &edge[start..end] {
+                        stable[coord.to_index()] = true;
+                    }
+                }
+                start = end;
+            }
+        }
+
+        stable.iter().filter(|&&s| s).count() as u8
+    }
+
+    /// Reflects the board along the a1-h8 diagonal, swapping the column
+    /// and row of every square.
+    pub fn mirror_diagonal(&self) -> Board {
+        let mut board = Board::new();
+        for coord in Coordinate::iter_all() {
+            if let Some(disk) = self.get_disk(coord) {
+                let (col, row) = coord.indices();
+                let mirrored =
+                    Coordinate::new((b'a' + row as u8) as char, col + 1);
+                board.place(mirrored, disk);
+            }
+        }
+
+        board
+    }
+
+    /// Swaps the color of every disk.
+    pub fn flip_all(&self) -> Board {
+        let mut board = Board::new();
+        for (&coord, disk) in self.disks.iter() {
+            board.place(coord, flip_disk(disk));
+        }
+
+        board
+    }
+
+    /// Turns the board half way round, so that a1 becomes h8.
+    pub fn rotate_180(&self) -> Board {
+        let mut board = Board::new();
+        for (&coord, &disk) in self.disks.iter() {
+            let (col, row) = coord.indices();
+            let rotated = Coordinate::new((b'h' - col as u8) as char, 8 - row);
+            board.place(rotated, disk);
+        }
+
+        board
+    }
+
+    /// Returns the eight boards that the rotations and reflections of the
+    /// square make of this one, starting with the board itself, then its
+    /// quarter turns clockwise, then the same for its a1-h8 reflection.
+    pub fn transforms(&self) -> [Board; 8] {
+        [
+            self.map_squares(|col, row| (col, row)),
+            self.map_squares(|col, row| (7 - row, col)),
+            self.map_squares(|col, row| (7 - col, 7 - row)),
+            self.map_squares(|col, row| (row, 7 - col)),
+            self.map_squares(|col, row| (row, col)),
+            self.map_squares(|col, row| (7 - col, row)),
+            self.map_squares(|col, row| (7 - row, 7 - col)),
+            self.map_squares(|col, row| (col, 7 - row)),
+        ]
+    }
+
+    /// Picks the transform with the smallest `to_compact_string`, which
+    /// is the same for every board symmetric to this one.
+    pub fn canonical(&self) -> Board {
+        let transforms = self.transforms();
+        let canonical = transforms
+            .iter()
+            .min_by_key(|board| board.to_compact_string())
+            .unwrap();
+
+        canonical.clone()
+    }
+
+    /// Moves the disk on each square to the `(column, row)` indices that
+    /// `to` gives for it.
+    fn map_squares(&self, to: fn(usize, usize) -> (usize, usize)) -> Board {
+        let mut board = Board::new();
+        for (&coord, &disk) in self.disks.iter() {
+            let (col, row) = coord.indices();
+            let (col, row) = to(col, row);
+            board.place(Coordinate::from_index(row * 8 + col).unwrap(), disk);
+        }
+
+        board
+    }
+
+    pub fn weighted_disk_score(
+        &self,
+        weights: &[[i32; 8]; 8],
+        disk: Disk,
+    ) -> i32 {
+        let mut score = 0;
+        for (coord, &other) in self.disks.iter() {
+            let (col, row) = coord.indices();
+            let weight = weights[row][col];
+            if other == disk {
+                score += weight;
+            } else {
+                score -= weight;
+            }
+        }
+
+        score
+    }
+
+    pub fn flip_count(&self, coord: Coordinate, disk: Disk) -> usize {
+        match self.try_move(coord, disk) {
+            Ok(board) => board.count(disk) - self.count(disk) - 1,
+            Err(_) => 0,
+        }
+    }
+
+    /// Returns the board after each legal move of `disk`, in the order of
+    /// `valid_moves`, or just this board when `disk` has to pass.
+    pub fn generate_all_successors(&self, disk: Disk) -> Vec<Board> {
+        let successors: Vec<Board> = self
+            .valid_moves(disk)
+            .into_iter()
+            .filter_map(|coord| self.try_move(coord, disk).ok())
+            .collect();
+        if successors.is_empty() {
+            return vec![self.clone()];
+        }
+
+        successors
+    }
+
+    /// Counts the positions reached after `depth` plies with `disk` to
+    /// move, a forced pass being a ply and a finished game a position of
+    /// its own, to check the move generator against known numbers.
+    pub fn perft(&self, disk: Disk, depth: u8) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+
+        let moves = self.valid_moves(disk);
+        let opponent = flip_disk(&disk);
+        if moves.is_empty() {
+            if self.valid_moves(opponent).is_empty() {
+                return 1;
+            }
+            return self.perft(opponent, depth - 1);
+        }
+        if depth == 1 {
+            return moves.len() as u64;
+        }
+
+        moves
+            .into_iter()
+            .filter_map(|coord| self.try_move(coord, disk).ok())
+            .map(|board| board.perft(opponent, depth - 1))
+            .sum()
+    }
+
+    /// Returns the squares that a move of `disk` at `coord` flips, in
+    /// row-major order, or none when the move is illegal.
+    pub fn flipped_squares(
+        &self,
+        coord: Coordinate,
+        disk: Disk,
+    ) -> Vec<Coordinate> {
+        match self.preview_move(coord, disk) {
+            Some(board) => Coordinate::iter_all()
+                .filter(|&square| square != coord)
+                .filter(|&square| {
+                    board.get_disk(square) != self.get_disk(square)
+                })
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Pairs each of `moves` with its flip count, most flips first and
+    /// otherwise in the given order.
+    pub fn count_flips(
+        &self,
+        moves: &[Coordinate],
+        disk: Disk,
+    ) -> Vec<(Coordinate, usize)> {
+        let mut counts: Vec<(Coordinate, usize)> = moves
+            .iter()
+            .map(|&coord| (coord, self.flip_count(coord, disk)))
+            .collect();
+        counts.sort_by_key(|&(_, count)| Reverse(count));
+
+        counts
+    }
+
+    pub fn valid_moves(&self, disk: Disk) -> Vec<Coordinate> {
+        Coordinate::iter_all()
+            .filter(|&coord| self.is_valid_move(coord, disk))
+            .collect()
+    }
+
+    /// Tells whether `disk` may play at `coord`, scanning the lines like
+    /// `try_move` but stopping at the first one that flips, without
+    /// cloning the board.
+    pub fn is_valid_move(&self, coord: Coordinate, disk: Disk) -> bool {
+        if self.get_disk(coord).is_some() {
+            return false;
+        }
+
+        let opponent = flip_disk(&disk);
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                if dx == 0 && dy == 0 {
+                    continue;
+                }
+                let mut offset = 1;
+                while let Ok(next) = coord + (dx * offset, dy * offset) {
+                    match self.get_disk(next) {
+                        Some(other) if other == opponent => offset += 1,
+                        Some(_) if offset > 1 => return true,
+                        _ => break,
+                    }
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Maps every legal move for `disk` to the number of disks it flips.
+    pub fn legal_moves_map(&self, disk: Disk) -> HashMap<Coordinate, usize> {
+        let count = self.count(disk);
+        Coordinate::iter_all()
+            .filter_map(|coord| match self.try_move(coord, disk) {
+                Ok(board) => Some((coord, board.count(disk) - count - 1)),
+                Err(_) => None,
+            })
+            .collect()
+    }
+
+    pub fn mobility(&self, disk: Disk) -> usize {
+        self.valid_moves(disk).len()
+    }
+
+    /// Counts the empty squares next to a disk of the opponent of `side`,
+    /// the squares where `side` may get moves later.
+    pub fn potential_mobility(&self, side: Side) -> usize {
+        let opponent = flip_disk(&side.to_disk());
+        Coordinate::iter_all()
+            .filter(|&coord| self.get_disk(coord).is_none())
+            .filter(|&coord| {
+                coord
+                    .neighbors_8()
+                    .any(|next| self.get_disk(next) == Some(opponent))
+            })
+            .count()
+    }
+
+    /// Renders the board on one line, a row of 8 squares at a time,
+    /// each row followed by a space.
+    pub fn render_compact(&self) -> String {
+        let mut output = String::with_capacity(72);
+        for row in Row::iter() {
+            for col in Column::iter() {
+                let coord = Coordinate::from_parts(col, row);
+                output.push(match self.get_disk(coord) {
+                    None => '.',
+                    Some(disk) => disk.to_char(),
+                });
+            }
+            output.push(' ');
+        }
+
+        output
+    }
+
+    /// Renders the board as a grid with column and row labels, one row
+    /// per line.
+    pub fn render_grid(&self) -> String {
+        self.render_grid_with('x', 'o', '.')
+    }
+
+    pub fn render_grid_with(
+        &self,
+        black: char,
+        white: char,
+        empty: char,
+    ) -> String {
+        self.render_grid_marked(black, white, empty, &[], None)
+    }
+
+    /// Like `render_grid_with`, but draws `*` on the empty squares among
+    /// `marks` and brackets the disk at `last_move`, such as `[x]`.
+    pub fn render_grid_marked(
+        &self,
+        black: char,
+        white: char,
+        empty: char,
+        marks: &[Coordinate],
+        last_move: Option<Coordinate>,
+    ) -> String {
+        let mut output = String::with_capacity(256);
+        output += "   a  b  c  d  e  f  g  h\n";
+        for row in Row::iter() {
+            output += format!("{} ", row).as_str();
+            for col in Column::iter() {
+                let coord = Coordinate::from_parts(col, row);
+                let symbol = match self.get_disk(coord) {
+                    None if marks.contains(&coord) => '*',
+                    None => empty,
+                    Some(Disk::Black) => black,
+                    Some(Disk::White) => white,
+                };
+                if last_move == Some(coord) {
+                    output += format!("[{}]", symbol).as_str();
+                } else {
+                    output += format!(" {} ", symbol).as_str();
+                }
+            }
+            output += "\n";
+        }
+
+        output
+    }
+
+    /// Encodes the board as 64 characters in row-major order, from a1 to
+    /// h8, with `.` for empty squares and no separators.
+    pub fn to_compact_string(&self) -> String {
+        Coordinate::iter_all()
+            .map(|coord| match self.get_disk(coord) {
+                None => '.',
+                Some(disk) => disk.to_char(),
+            })
+            .collect()
+    }
+
+    pub fn from_compact_string(s: &str) -> Result<Board, ParseBoardErr> {
+        let cells: Vec<char> = s.chars().collect();
+        if cells.len() != 64 {
+            return Err(ParseBoardErr::WrongLength(cells.len()));
+        }
+
+        let mut board = Board::new();
+        for (coord, &c) in Coordinate::iter_all().zip(cells.iter()) {
+            if let Some(disk) = Disk::from_char(c) {
+                board.place(coord, disk);
+            } else if !is_empty_char(c) {
+                return Err(ParseBoardErr::InvalidChar(c));
+            }
+        }
+
+        Ok(board)
+    }
+
+    /// Encodes the black and white occupancy as two 64-bit masks, bit 0
+    /// being a1 and bit 63 h8, written as 16 hex digits each.
+    pub fn to_compact_hex(&self) -> String {
+        let mut black = 0u64;
+        let mut white = 0u64;
+        for (&coord, &disk) in self.disks.iter() {
+            let bit = 1 << coord.to_index();
+            match disk {
+                Disk::Black => black |= bit,
+                Disk::White => white |= bit,
+            }
+        }
+
+        format!("{:016x}{:016x}", black, white)
+    }
+
+    pub fn from_compact_hex(s: &str) -> Result<Board, HexError> {
+        if s.len() != 32 || !s.is_ascii() {
+            return Err(HexError::WrongLength(s.chars().count()));
+        }
+        if let Some(c) = s.chars().find(|c| !c.is_ascii_hexdigit()) {
+            return Err(HexError::InvalidDigit(c));
+        }
+
+        let black = u64::from_str_radix(&s[..16], 16).unwrap();
+        let white = u64::from_str_radix(&s[16..], 16).unwrap();
+        if black & white != 0 {
+            return Err(HexError::Overlap);
+        }
+
+        let mut board = Board::new();
+        for coord in Coordinate::iter_all() {
+            let bit = 1 << coord.to_index();
+            if black & bit != 0 {
+                board.place(coord, Disk::Black);
+            } else if white & bit != 0 {
+                board.place(coord, Disk::White);
+            }
+        }
+
+        Ok(board)
+    }
+
+    /// Plays the moves in order, returning the zero-based index of the
+    /// first illegal one along with the reason.
+    pub fn apply_move_sequence(
+        &self,
+        moves: &[(Coordinate, Disk)],
+    ) -> Result<Board, (usize, MoveErr)> {
+        let mut board = self.clone();
+        for (step, &(coord, disk)) in moves.iter().enumerate() {
+            board = board.try_move(coord, disk).map_err(|err| (step, err))?;
+        }
+
+        Ok(board)
+    }
+
+    /// Plays from this position with black to move, asking `sides` for a
+    /// move on the current board whenever the side to move has one. The
+    /// game ends when neither side can move, or early when `sides` gives
+    /// `None` or an illegal move. Returns the final board, the winner by
+    /// disk count (`None` for a draw) and the number of moves played.
+    pub fn play_game(
+        &self,
+        sides: &mut dyn FnMut(&Board, Side) -> Option<Coordinate>,
+    ) -> (Board, Option<Side>, u32) {
+        let mut board = self.clone();
+        let mut side = Side::Dark;
+        let mut moves = 0;
+
+        loop {
+            let disk = match side {
+                Side::Dark => Disk::Black,
+                Side::Light => Disk::White,
+            };
+            if board.valid_moves(disk).is_empty() {
+                if board.valid_moves(flip_disk(&disk)).is_empty() {
+                    break;
+                }
+            } else {
+                let next = sides(&board, side)
+                    .and_then(|coord| board.try_move(coord, disk).ok());
+                match next {
+                    Some(next) => board = next,
+                    None => break,
+                }
+                moves += 1;
+            }
+            side = match side {
+                Side::Dark => Side::Light,
+                Side::Light => Side::Dark,
+            };
+        }
+
+        let winner =
+            match board.count(Disk::Black).cmp(&board.count(Disk::White)) {
+                Ordering::Greater => Some(Side::Dark),
+                Ordering::Less => Some(Side::Light),
+                Ordering::Equal => None,
+            };
+
+        (board, winner, moves)
+    }
+
+    /// Returns the board after `disk` plays at `coord`, or `None` when the
+    /// move is illegal, leaving `self` untouched. This is the entry point
+    /// for showing a move before it is played. `try_move` flips disks on
+    /// a clone, pushing them on the clone's `stack` and committing or
+    /// aborting each line, so the board returned has an empty stack.
+    pub fn preview_move(
+        &self,
+        coord: Coordinate,
+        disk: Disk,
+    ) -> Option<Board> {
+        let board = self.try_move(coord, disk).ok()?;
+        debug_assert!(board.stack.is_empty(), "flips left on the stack");
+
+        Some(board)
+    }
+
+    /// Like `try_move`, but takes a raw column and row, such as user
+    /// input, and reports those off the board as `OutOfBounds`.
+    pub fn try_move_at(
+        &self,
+        col: char,
+        row: usize,
+        disk: Disk,
+    ) -> Result<Board, MoveErr> {
+        match Coordinate::try_new(col, row) {
+            Some(coord) => self.try_move(coord, disk),
+            None => Err(MoveErr::OutOfBounds),
+        }
+    }
+
     pub fn try_move(
         &self,
         coord: Coordinate,
@@ -143,24 +825,42 @@ impl Board {
     }
 }
 
+/// Returns the squares of the rows 1 and 8 and of the columns a and h,
+/// each in order from one corner to the other.
+fn edges() -> [Vec<Coordinate>; 4] {
+    [
+        (0..8).map(|i| Coordinate::from_index(i).unwrap()).collect(),
+        (56..64)
+            .map(|i| Coordinate::from_index(i).unwrap())
+            .collect(),
+        (0..8)
+            .map(|i| Coordinate::from_index(i * 8).unwrap())
+            .collect(),
+        (0..8)
+            .map(|i| Coordinate::from_index(i * 8 + 7).unwrap())
+            .collect(),
+    ]
+}
+
+impl fmt::Debug for Board {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Board({})", self.to_compact_string())
+    }
+}
+
+/// Formats the board with `render_compact`.
 impl fmt::Display for Board {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        for row in 1..=8 {
-            for col in 'a'..='h' {
-                let coord = Coordinate::new(col, row);
-                let symbol = match self.get_disk(coord) {
-                    None => '.',
-                    Some(Disk::Black) => 'x',
-                    Some(Disk::White) => 'o',
-                };
-                write!(f, "{}", symbol)?;
-                if col == 'h' {
-                    write!(f, " ")?;
-                }
-            }
-        }
+        write!(f, "{}", self.render_compact())
+    }
+}
+
+impl FromStr for Board {
+    type Err = ParseBoardErr;
 
-        Ok(())
+    fn from_str(s: &str) -> Result<Board, ParseBoardErr> {
+        let compact: String = s.split_whitespace().collect();
+        Board::from_compact_string(&compact)
     }
 }
 
@@ -173,7 +873,14 @@ mod tests {
 
     use super::Board;
     use super::Coordinate;
-    use super::MoveErr;
+    use super::{HexError, MoveErr, ParseBoardErr};
+    use crate::engine::Side;
+    use crate::heuristic::{GamePhase, Parity, STANDARD_WEIGHTS};
+    use rand::rngs::SmallRng;
+    use rand::seq::SliceRandom;
+    use rand::{Rng, SeedableRng};
+    use std::cmp::Ordering;
+    use std::collections::HashMap;
 
     #[test]
     fn flip_disk_and_disk_eq() {
@@ -184,14 +891,474 @@ mod tests {
     }
 
     #[test]
-    fn board_display() {
+    fn board_apply_move_sequence() {
+        let mut board = Board::new();
+        board.init();
+
+        let moves = [
+            (Coordinate::new('f', 5), Disk::Black),
+            (Coordinate::new('d', 6), Disk::White),
+            (Coordinate::new('c', 3), Disk::Black),
+        ];
+        let expected = board
+            .try_move(moves[0].0, moves[0].1)
+            .and_then(|b| b.try_move(moves[1].0, moves[1].1))
+            .and_then(|b| b.try_move(moves[2].0, moves[2].1))
+            .unwrap();
+        assert_eq!(board.apply_move_sequence(&moves), Ok(expected));
+
+        let moves = [
+            (Coordinate::new('f', 5), Disk::Black),
+            (Coordinate::new('f', 5), Disk::White),
+        ];
+        let result = board.apply_move_sequence(&moves);
+        assert_eq!(result, Err((1, MoveErr::NotEmpty)));
+
+        let moves = [
+            (Coordinate::new('f', 5), Disk::Black),
+            (Coordinate::new('d', 6), Disk::White),
+            (Coordinate::new('a', 1), Disk::Black),
+        ];
+        let result = board.apply_move_sequence(&moves);
+        assert_eq!(result, Err((2, MoveErr::NoDiskFlipped)));
+    }
+
+    #[test]
+    fn board_play_game() {
+        let mut board = Board::new();
+        board.init();
+
+        let mut play = |current: &Board, side: Side| {
+            let disk = match side {
+                Side::Dark => Disk::Black,
+                Side::Light => Disk::White,
+            };
+            Some(current.valid_moves(disk)[0])
+        };
+        let (end, winner, moves) = board.play_game(&mut play);
+        assert!(end.valid_moves(Disk::Black).is_empty());
+        assert!(end.valid_moves(Disk::White).is_empty());
+        assert_eq!(
+            moves as usize,
+            end.count(Disk::Black) + end.count(Disk::White) - 4
+        );
+
+        let expected =
+            match end.count(Disk::Black).cmp(&end.count(Disk::White)) {
+                Ordering::Greater => Some(Side::Dark),
+                Ordering::Less => Some(Side::Light),
+                Ordering::Equal => None,
+            };
+        assert_eq!(winner, expected);
+    }
+
+    #[test]
+    fn board_play_game_ends_without_a_move() {
+        let mut board = Board::new();
+        board.init();
+
+        let f5 = Coordinate::new('f', 5);
+        let mut play = |_: &Board, side: Side| match side {
+            Side::Dark => Some(f5),
+            Side::Light => None,
+        };
+        let (end, winner, moves) = board.play_game(&mut play);
+        assert_eq!(end, board.try_move(f5, Disk::Black).unwrap());
+        assert_eq!(winner, Some(Side::Dark));
+        assert_eq!(moves, 1);
+
+        // Playing f5 again is illegal, which also ends the game.
+        let mut play = |_: &Board, _: Side| Some(f5);
+        let (end, _, moves) = board.play_game(&mut play);
+        assert_eq!(end, board.try_move(f5, Disk::Black).unwrap());
+        assert_eq!(moves, 1);
+    }
+
+    #[test]
+    fn board_phase() {
+        let mut board = Board::new();
+        board.init();
+        assert_eq!(board.phase(), GamePhase::Opening);
+
+        let board: Board = "\
+xxxxxxxx xxxxxxxx xxxxxxxx ........ ........ ........ ........ ........"
+            .parse()
+            .unwrap();
+        assert_eq!(board.phase(), GamePhase::Midgame);
+
+        let board: Board = "\
+xxxxxxxx xxxxxxxx xxxxxxxx oooooooo oooooooo oooooooo ........ ........"
+            .parse()
+            .unwrap();
+        assert_eq!(board.phase(), GamePhase::Endgame);
+        assert_eq!(board.count(Disk::Black), 24);
+
+        let board: Board = "\
+xxxxxxxo xxxxxxxx xxxxxxxx xxxxxxxx oooooooo oooooooo oooooooo ooooo..."
+            .parse()
+            .unwrap();
+        assert_eq!(board.phase(), GamePhase::Endgame);
+    }
+
+    #[test]
+    fn board_parity() {
+        let mut board = Board::new();
+        assert_eq!(board.empty_count(), 64);
+        board.init();
+        assert_eq!(board.empty_count(), 60);
+        assert_eq!(board.parity(), Parity::Even);
+
+        let board = board.try_move(Coordinate::new('f', 5), Disk::Black);
+        let board = board.unwrap();
+        assert_eq!(board.empty_count(), 59);
+        assert_eq!(board.parity(), Parity::Odd);
+    }
+
+    #[test]
+    fn board_zobrist_hash() {
+        let mut board = Board::new();
+        board.init();
+        assert_ne!(board.zobrist_hash(), 0);
+        assert_eq!(board.zobrist_hash(), board.clone().zobrist_hash());
+        assert_ne!(
+            board.zobrist_hash_with_side(Side::Dark),
+            board.zobrist_hash_with_side(Side::Light)
+        );
+
+        let moved = board.try_move(Coordinate::new('f', 5), Disk::Black);
+        let moved = moved.unwrap();
+        assert_ne!(moved.zobrist_hash(), board.zobrist_hash());
+
+        let mut rng = SmallRng::seed_from_u64(42);
+        let mut seen: HashMap<u64, Board> = HashMap::new();
+        for _ in 0..100 {
+            let mut position = board.clone();
+            let mut disk = Disk::Black;
+            for _ in 0..rng.gen_range(1, 30) {
+                let moves = position.valid_moves(disk);
+                if let Some(&coord) = moves.choose(&mut rng) {
+                    position = position.try_move(coord, disk).unwrap();
+                }
+                disk = flip_disk(&disk);
+            }
+
+            let hash = position.zobrist_hash();
+            if let Some(other) = seen.get(&hash) {
+                assert_eq!(*other, position);
+            }
+            seen.insert(hash, position);
+        }
+        assert!(seen.len() > 50);
+    }
+
+    #[test]
+    fn board_legal_moves_map() {
         let mut board = Board::new();
         board.init();
+        let map = board.legal_moves_map(Disk::Black);
+        assert_eq!(map.len(), 4);
+        for coord in board.valid_moves(Disk::Black) {
+            assert_eq!(map.get(&coord), Some(&1));
+        }
+
+        let board: Board = "\
+.ox..... ........ ........ xooo.... ........ ........ ........ ........"
+            .parse()
+            .unwrap();
+        let map = board.legal_moves_map(Disk::Black);
+        assert_eq!(map.len(), 2);
+        assert_eq!(map[&Coordinate::new('a', 1)], 1);
+        assert_eq!(map[&Coordinate::new('e', 4)], 3);
+    }
+
+    #[test]
+    fn board_corners() {
+        let board: Board = "\
+x......o ........ ........ ........ ........ ........ ........ .......x"
+            .parse()
+            .unwrap();
+        let corners = board.corners();
+        assert_eq!(corners[0], (Coordinate::new('a', 1), Some(Disk::Black)));
+        assert_eq!(corners[1], (Coordinate::new('h', 1), Some(Disk::White)));
+        assert_eq!(corners[2], (Coordinate::new('a', 8), None));
+        assert_eq!(corners[3], (Coordinate::new('h', 8), Some(Disk::Black)));
+        assert_eq!(board.corners_held(Disk::Black), 2);
+        assert_eq!(board.corners_held(Disk::White), 1);
+    }
+
+    #[test]
+    fn disk_from_char() {
+        for c in "xXbB".chars() {
+            assert_eq!(Disk::from_char(c), Some(Disk::Black));
+        }
+        for c in "oOwW".chars() {
+            assert_eq!(Disk::from_char(c), Some(Disk::White));
+        }
+        for c in ".- ".chars() {
+            assert_eq!(Disk::from_char(c), None);
+        }
+        for c in "az0#".chars() {
+            assert_eq!(Disk::from_char(c), None);
+        }
+    }
+
+    #[test]
+    fn disk_to_char() {
+        assert_eq!(Disk::Black.to_char(), 'x');
+        assert_eq!(Disk::White.to_char(), 'o');
+        assert_eq!(Disk::from_char(Disk::Black.to_char()), Some(Disk::Black));
+        assert_eq!(Disk::from_char(Disk::White.to_char()), Some(Disk::White));
+    }
+
+    #[test]
+    fn board_from_str() {
+        let input = "\
+........ ........ ........ ...ox... ...xo... ........ ........ ........ ";
+        let board: Board = input.parse().unwrap();
+        assert_eq!(board.render_compact(), input);
+
+        let input = "\
+-------- -------- -------- ---WB--- ---BW--- -------- -------- --------";
+        let board: Board = input.parse().unwrap();
         let output = "\
 ........ ........ ........ ...ox... ...xo... ........ ........ ........ ";
+        assert_eq!(board.render_compact(), output);
+
+        let result = "........".parse::<Board>();
+        assert_eq!(result.err(), Some(ParseBoardErr::WrongLength(8)));
+
+        let input = "\
+........ ........ ........ ...o?... ...xo... ........ ........ ........ ";
+        let result = input.parse::<Board>();
+        assert_eq!(result.err(), Some(ParseBoardErr::InvalidChar('?')));
+    }
+
+    #[test]
+    fn board_count_and_valid_moves() {
+        let mut board = Board::new();
+        board.init();
+        assert_eq!(board.count(Disk::Black), 2);
+        assert_eq!(board.count(Disk::White), 2);
+
+        let moves = board.valid_moves(Disk::Black);
+        let expected = vec![
+            Coordinate::new('d', 3),
+            Coordinate::new('c', 4),
+            Coordinate::new('f', 5),
+            Coordinate::new('e', 6),
+        ];
+        assert_eq!(moves, expected);
+
+        let board = Board::new();
+        assert!(board.valid_moves(Disk::Black).is_empty());
+    }
+
+    #[test]
+    fn board_mirror_diagonal() {
+        let board: Board = "\
+.x...... ........ ........ ...ox... ...xo... ........ ........ .......o"
+            .parse()
+            .unwrap();
+        let mirrored = board.mirror_diagonal();
+        let output = "\
+........ x....... ........ ...ox... ...xo... ........ ........ .......o ";
+        assert_eq!(mirrored.render_compact(), output);
+        assert_eq!(mirrored.mirror_diagonal(), board);
+
+        let mut board = Board::new();
+        board.init();
+        assert_eq!(board.mirror_diagonal(), board);
+    }
+
+    #[test]
+    fn board_flip_all_and_rotate_180() {
+        let board: Board = "\
+xo...... ........ ........ ...ox... ...xo... ........ ........ .......o"
+            .parse()
+            .unwrap();
+
+        let flipped = board.flip_all();
+        let output = "\
+ox...... ........ ........ ...xo... ...ox... ........ ........ .......x ";
+        assert_eq!(flipped.render_compact(), output);
+        assert_eq!(flipped.flip_all(), board);
+
+        let rotated = board.rotate_180();
+        let output = "\
+o....... ........ ........ ...ox... ...xo... ........ ........ ......ox ";
+        assert_eq!(rotated.render_compact(), output);
+        assert_eq!(rotated.rotate_180(), board);
+
+        let mut board = Board::new();
+        board.init();
+        assert_eq!(board.rotate_180(), board);
+        assert_ne!(board.flip_all(), board);
+    }
+
+    #[test]
+    fn board_weighted_disk_score() {
+        let mut board = Board::new();
+        board.init();
+        assert_eq!(
+            board.weighted_disk_score(&STANDARD_WEIGHTS, Disk::Black),
+            0
+        );
+
+        let mut with_corner = board.clone();
+        with_corner.place(Coordinate::new('a', 1), Disk::Black);
+        let score =
+            with_corner.weighted_disk_score(&STANDARD_WEIGHTS, Disk::Black);
+        assert_eq!(score, 100);
+        let score =
+            with_corner.weighted_disk_score(&STANDARD_WEIGHTS, Disk::White);
+        assert_eq!(score, -100);
+    }
+
+    #[test]
+    fn board_flip_count() {
+        let board: Board = "\
+........ ........ ........ ..xoooo. ........ ........ ........ ........"
+            .parse()
+            .unwrap();
+        assert_eq!(board.flip_count(Coordinate::new('h', 4), Disk::Black), 4);
+        assert_eq!(board.flip_count(Coordinate::new('b', 4), Disk::Black), 0);
+        assert_eq!(board.flip_count(Coordinate::new('c', 4), Disk::Black), 0);
+
+        let b4 = Coordinate::new('b', 4);
+        let h4 = Coordinate::new('h', 4);
+        let counts = board.count_flips(&[b4, h4], Disk::Black);
+        assert_eq!(counts, vec![(h4, 4), (b4, 0)]);
+    }
+
+    #[test]
+    fn board_count_flips() {
+        let mut board = Board::new();
+        board.init();
+        let moves = board.valid_moves(Disk::Black);
+        let counts = board.count_flips(&moves, Disk::Black);
+        assert_eq!(counts.len(), 4);
+        for &(coord, count) in &counts {
+            assert!(moves.contains(&coord));
+            assert_eq!(count, 1);
+        }
+    }
+
+    #[test]
+    fn board_generate_all_successors() {
+        let mut board = Board::new();
+        board.init();
+        let successors = board.generate_all_successors(Disk::Black);
+        assert_eq!(successors.len(), 4);
+        for next in &successors {
+            assert_eq!(next.count(Disk::Black), 4);
+            assert_eq!(next.count(Disk::White), 1);
+        }
+
+        let board: Board = "\
+xo...... ........ ........ ........ ........ ........ ........ ........"
+            .parse()
+            .unwrap();
+        assert_eq!(board.generate_all_successors(Disk::White), vec![board]);
+    }
+
+    #[test]
+    fn board_snapshot_restore() {
+        let mut board = Board::new();
+        board.init();
+        let original = board.clone();
+        let snap = board.snapshot();
+
+        for &(col, row, disk) in &[
+            ('f', 5, Disk::Black),
+            ('f', 6, Disk::White),
+            ('e', 6, Disk::Black),
+        ] {
+            board = board.try_move(Coordinate::new(col, row), disk).unwrap();
+        }
+        assert_ne!(board, original);
+        assert_ne!(board.snapshot(), snap);
+
+        board.restore(&snap);
+        assert_eq!(board, original);
+        assert_eq!(board.snapshot(), snap);
+    }
+
+    #[test]
+    fn board_compact_hex() {
+        let mut board = Board::new();
+        assert_eq!(board.to_compact_hex(), "0".repeat(32));
+
+        board.init();
+        assert_eq!(board.to_compact_hex(), "00000008100000000000001008000000");
+        assert_eq!(board.to_compact_hex(), board.clone().to_compact_hex());
+
+        let board = board.try_move(Coordinate::new('f', 5), Disk::Black);
+        let board = board.unwrap();
+        let decoded = Board::from_compact_hex(&board.to_compact_hex());
+        let decoded = decoded.unwrap();
+        assert_eq!(decoded.to_compact_string(), board.to_compact_string());
+
+        let hex = board.to_compact_hex();
+        let result = Board::from_compact_hex(&hex[1..]);
+        assert_eq!(result.err(), Some(HexError::WrongLength(31)));
+        let result = Board::from_compact_hex(&hex.replace('0', "g"));
+        assert_eq!(result.err(), Some(HexError::InvalidDigit('g')));
+        let result = Board::from_compact_hex(&"1".repeat(32));
+        assert_eq!(result.err(), Some(HexError::Overlap));
+    }
+
+    #[test]
+    fn board_compact_string() {
+        let mut board = Board::new();
+        assert_eq!(board.to_compact_string(), ".".repeat(64));
+
+        board.init();
+        let compact = board.to_compact_string();
+        assert_eq!(compact.len(), 64);
+        assert_eq!(
+            compact,
+            "...........................ox......xo..........................."
+        );
+
+        let board = board.try_move(Coordinate::new('f', 5), Disk::Black);
+        let board = board.unwrap();
+        let compact = board.to_compact_string();
+        assert_eq!(compact.len(), 64);
+        let decoded = Board::from_compact_string(&compact).unwrap();
+        assert_eq!(decoded.render_compact(), board.render_compact());
+
+        let result = Board::from_compact_string(&compact[1..]);
+        assert_eq!(result.err(), Some(ParseBoardErr::WrongLength(63)));
+        let result = Board::from_compact_string(&format!("{} ", compact));
+        assert_eq!(result.err(), Some(ParseBoardErr::WrongLength(65)));
+    }
+
+    #[test]
+    fn board_render_compact() {
+        let mut board = Board::new();
+        board.init();
+        let output = "\
+........ ........ ........ ...ox... ...xo... ........ ........ ........ ";
+        assert_eq!(board.render_compact(), output);
         assert_eq!(board.to_string(), output);
     }
 
+    #[test]
+    fn board_render_grid() {
+        let mut board = Board::new();
+        board.init();
+        let output = "   a  b  c  d  e  f  g  h\n\
+1  .  .  .  .  .  .  .  . \n\
+2  .  .  .  .  .  .  .  . \n\
+3  .  .  .  .  .  .  .  . \n\
+4  .  .  .  o  x  .  .  . \n\
+5  .  .  .  x  o  .  .  . \n\
+6  .  .  .  .  .  .  .  . \n\
+7  .  .  .  .  .  .  .  . \n\
+8  .  .  .  .  .  .  .  . \n";
+        assert_eq!(board.render_grid(), output);
+    }
+
     #[test]
     #[should_panic]
     fn board_place_not_empty() {
@@ -219,7 +1386,7 @@ mod tests {
 
         let output = "\
 ........ ........ ........ ...xo... ...ox... ........ ........ ........ ";
-        assert_eq!(board.to_string(), output);
+        assert_eq!(board.render_compact(), output);
     }
 
     #[test]
@@ -234,13 +1401,13 @@ mod tests {
         assert_eq!(board.stack.len(), 4);
         let output = "\
 ........ ........ ........ ...xo... ...ox... ........ ........ ........ ";
-        assert_eq!(board.to_string(), output);
+        assert_eq!(board.render_compact(), output);
 
         board.abort();
         assert_eq!(board.stack.len(), 0);
         let output = "\
 ........ ........ ........ ...ox... ...xo... ........ ........ ........ ";
-        assert_eq!(board.to_string(), output);
+        assert_eq!(board.render_compact(), output);
     }
 
     #[test]
@@ -255,13 +1422,13 @@ mod tests {
         assert_eq!(board.stack.len(), 4);
         let output = "\
 ........ ........ ........ ...xo... ...ox... ........ ........ ........ ";
-        assert_eq!(board.to_string(), output);
+        assert_eq!(board.render_compact(), output);
 
         board.commit();
         assert_eq!(board.stack.len(), 0);
         let output = "\
 ........ ........ ........ ...xo... ...ox... ........ ........ ........ ";
-        assert_eq!(board.to_string(), output);
+        assert_eq!(board.render_compact(), output);
     }
 
     #[test]
@@ -273,7 +1440,7 @@ mod tests {
         let board = result.unwrap();
         let output = "\
 ........ ........ ........ ...ox... ...xxx.. ........ ........ ........ ";
-        assert_eq!(board.to_string(), output);
+        assert_eq!(board.render_compact(), output);
 
         let result = board.try_move(Coordinate::new('f', 5), Disk::White);
         assert_eq!(result.err(), Some(MoveErr::NotEmpty));
@@ -281,4 +1448,194 @@ mod tests {
         let result = board.try_move(Coordinate::new('f', 4), Disk::Black);
         assert_eq!(result.err(), Some(MoveErr::NoDiskFlipped));
     }
+
+    #[test]
+    fn board_try_move_at() {
+        let mut board = Board::new();
+        board.init();
+
+        let result = board.try_move_at('f', 5, Disk::Black);
+        let expected = board.try_move(Coordinate::new('f', 5), Disk::Black);
+        assert_eq!(result, expected);
+
+        let result = board.try_move_at('z', 5, Disk::Black);
+        assert_eq!(result.err(), Some(MoveErr::OutOfBounds));
+        let result = board.try_move_at('f', 99, Disk::Black);
+        assert_eq!(result.err(), Some(MoveErr::OutOfBounds));
+        let result = board.try_move_at('a', 0, Disk::Black);
+        assert_eq!(result.err(), Some(MoveErr::OutOfBounds));
+        let result = board.try_move_at('d', 4, Disk::Black);
+        assert_eq!(result.err(), Some(MoveErr::NotEmpty));
+    }
+
+    #[test]
+    fn board_potential_mobility() {
+        let mut board = Board::new();
+        assert_eq!(board.potential_mobility(Side::Dark), 0);
+        board.init();
+        assert_eq!(board.potential_mobility(Side::Dark), 10);
+        assert_eq!(board.potential_mobility(Side::Light), 10);
+
+        let board = board.try_move(Coordinate::new('f', 5), Disk::Black);
+        let board = board.unwrap();
+        assert_eq!(board.potential_mobility(Side::Dark), 5);
+        assert_eq!(board.potential_mobility(Side::Light), 13);
+        let board = board.try_move(Coordinate::new('f', 6), Disk::White);
+        let board = board.unwrap();
+        assert!(board.potential_mobility(Side::Dark) > 10);
+    }
+
+    #[test]
+    fn board_flipped_squares() {
+        let mut board = Board::new();
+        board.init();
+        let flipped =
+            board.flipped_squares(Coordinate::new('f', 5), Disk::Black);
+        assert_eq!(flipped, vec![Coordinate::new('e', 5)]);
+        assert!(board
+            .flipped_squares(Coordinate::new('a', 1), Disk::Black)
+            .is_empty());
+        assert!(board
+            .flipped_squares(Coordinate::new('d', 4), Disk::Black)
+            .is_empty());
+    }
+
+    #[test]
+    fn board_edge_stability_count() {
+        let board: Board = "\
+xxxxxxxx ........ ........ ........ ........ ........ ........ ........"
+            .parse()
+            .unwrap();
+        assert!(board.edge_stability_count(Disk::Black) >= 8);
+        assert_eq!(board.edge_stability_count(Disk::White), 0);
+
+        let board: Board = "\
+...x.... ........ ........ ........ ........ ........ ........ ........"
+            .parse()
+            .unwrap();
+        assert_eq!(board.edge_stability_count(Disk::Black), 0);
+
+        let board: Board = "\
+x...oxxo ........ ........ x....... ........ ........ ........ x......."
+            .parse()
+            .unwrap();
+        assert_eq!(board.edge_stability_count(Disk::Black), 4);
+        assert_eq!(board.edge_stability_count(Disk::White), 1);
+    }
+
+    #[test]
+    fn board_preview_move() {
+        let mut board = Board::new();
+        board.init();
+        let before = board.render_compact();
+
+        let coord = Coordinate::new('f', 5);
+        let preview = board.preview_move(coord, Disk::Black).unwrap();
+        assert!(preview.stack.is_empty());
+        assert_eq!(preview, board.try_move(coord, Disk::Black).unwrap());
+        assert_eq!(board.render_compact(), before);
+        assert!(board.stack.is_empty());
+
+        let coord = Coordinate::new('a', 1);
+        assert_eq!(board.preview_move(coord, Disk::Black), None);
+        let coord = Coordinate::new('d', 4);
+        assert_eq!(board.preview_move(coord, Disk::Black), None);
+        assert_eq!(board.render_compact(), before);
+    }
+
+    #[test]
+    fn board_is_valid_move() {
+        let mut board = Board::new();
+        board.init();
+        assert!(board.is_valid_move(Coordinate::new('f', 5), Disk::Black));
+        assert!(!board.is_valid_move(Coordinate::new('d', 4), Disk::Black));
+        assert!(!board.is_valid_move(Coordinate::new('f', 4), Disk::Black));
+        assert!(!board.is_valid_move(Coordinate::new('a', 1), Disk::White));
+
+        let mut rng = SmallRng::seed_from_u64(7);
+        let mut disk = Disk::Black;
+        for _ in 0..60 {
+            for coord in Coordinate::iter_all() {
+                assert_eq!(
+                    board.is_valid_move(coord, disk),
+                    board.try_move(coord, disk).is_ok()
+                );
+            }
+            let moves = board.valid_moves(disk);
+            if let Some(&coord) = moves.choose(&mut rng) {
+                board = board.try_move(coord, disk).unwrap();
+            }
+            disk = flip_disk(&disk);
+        }
+    }
+
+    #[test]
+    fn board_perft() {
+        let mut board = Board::new();
+        board.init();
+        let counts: Vec<u64> = (0..=5)
+            .map(|depth| board.perft(Disk::Black, depth))
+            .collect();
+        assert_eq!(counts, vec![1, 4, 12, 56, 244, 1396]);
+
+        let board: Board = "\
+ox...... ........ ........ ........ ........ ........ ........ ........"
+            .parse()
+            .unwrap();
+        // Black passes, white plays c1, and then neither side can move.
+        assert_eq!(board.perft(Disk::Black, 1), 1);
+        assert_eq!(board.perft(Disk::Black, 2), 1);
+        assert_eq!(board.perft(Disk::Black, 5), 1);
+    }
+
+    #[test]
+    fn board_transforms_and_canonical() {
+        let board: Board = "\
+xo...... ........ ........ ...ox... ...xx... .....o.. ........ .......o"
+            .parse()
+            .unwrap();
+        let transforms = board.transforms();
+        assert_eq!(transforms[0], board);
+        assert_eq!(transforms[2], board.rotate_180());
+        assert_eq!(transforms[4], board.mirror_diagonal());
+        for (i, transform) in transforms.iter().enumerate() {
+            assert_eq!(transform.count(Disk::Black), board.count(Disk::Black));
+            assert_eq!(transform.canonical(), board.canonical());
+            for other in &transforms[i + 1..] {
+                assert_ne!(transform, other);
+            }
+        }
+
+        let mut start = Board::new();
+        start.init();
+        assert_eq!(start.canonical(), start);
+        let f5 = start.try_move(Coordinate::new('f', 5), Disk::Black);
+        let d3 = start.try_move(Coordinate::new('d', 3), Disk::Black);
+        assert_eq!(f5.unwrap().canonical(), d3.unwrap().canonical());
+        assert_ne!(board.canonical(), board.flip_all().canonical());
+        assert_ne!(start.canonical(), board.canonical());
+    }
+
+    #[test]
+    fn board_count_wedges() {
+        let board: Board = "\
+..o.o... ........ ........ ...ox... ...xo... ........ ........ ........"
+            .parse()
+            .unwrap();
+        for transform in board.transforms().iter() {
+            assert_eq!(transform.count_wedges(Disk::Black), 1);
+            assert_eq!(transform.count_wedges(Disk::White), 0);
+        }
+
+        let board: Board = "\
+.o.o.o.. x....... ........ x....... x....... ........ ........ ......xo"
+            .parse()
+            .unwrap();
+        assert_eq!(board.count_wedges(Disk::Black), 2);
+        assert_eq!(board.count_wedges(Disk::White), 1);
+
+        let mut board = Board::new();
+        board.init();
+        assert_eq!(board.count_wedges(Disk::Black), 0);
+    }
 }
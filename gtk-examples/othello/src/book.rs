@@ -0,0 +1,201 @@
+use crate::board::{Board, Disk};
+use crate::engine::Engine;
+use crate::position::Coordinate;
+use crate::replay::TranscriptError;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+#[derive(Debug)]
+pub enum BookError {
+    Io(io::Error),
+    Transcript(usize, TranscriptError),
+}
+
+impl fmt::Display for BookError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BookError::Io(err) => write!(f, "{}", err),
+            BookError::Transcript(line, err) => {
+                write!(f, "line {}: {}", line, err)
+            }
+        }
+    }
+}
+
+impl From<io::Error> for BookError {
+    fn from(err: io::Error) -> BookError {
+        BookError::Io(err)
+    }
+}
+
+// ---------------------------------------------------------------------
+
+/// Moves known from the transcripts of games that start from the usual
+/// position. Positions are looked up by `Board::canonical`, so a line
+/// also covers its rotations and reflections.
+#[derive(Debug, Default)]
+pub struct OpeningBook {
+    /// The boards after each booked move, turned like the canonical board
+    /// before it, by that canonical board and the side to move. Turning
+    /// them alike keeps the move itself from a symmetric position such
+    /// as the start, where all four moves lead to the same canonical
+    /// board.
+    positions: HashMap<String, Vec<String>>,
+}
+
+impl OpeningBook {
+    /// Reads a book with one transcript a line, such as `f5d6c3`.
+    /// Blank lines and lines starting with `#` are skipped.
+    pub fn load(text: &str) -> Result<OpeningBook, BookError> {
+        let mut book = OpeningBook::default();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut engine = Engine::new();
+            engine
+                .load_transcript(line)
+                .map_err(|err| BookError::Transcript(index + 1, err))?;
+            book.add_game(&engine);
+        }
+
+        Ok(book)
+    }
+
+    pub fn from_file(path: &Path) -> Result<OpeningBook, BookError> {
+        OpeningBook::load(&fs::read_to_string(path)?)
+    }
+
+    /// Returns the number of positions with a booked move.
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Returns the move first booked for `disk` on `board`, turned to
+    /// match the board, or `None` when the position isn't in the book.
+    pub fn book_move(&self, board: &Board, disk: Disk) -> Option<Coordinate> {
+        let booked = self.positions.get(&key(board, disk))?.first()?;
+        let turn = canonical_transform(board);
+        board.valid_moves(disk).into_iter().find(|&coord| {
+            let next = board.try_move(coord, disk).unwrap();
+            next.transforms()[turn].to_compact_string() == *booked
+        })
+    }
+
+    /// Books each move of a replayed game, leaving out passes, which
+    /// need no book.
+    fn add_game(&mut self, engine: &Engine) {
+        let boards = engine.history_boards();
+        let moves = engine.move_history();
+        let mut disk = Disk::Black;
+        for (coord, pair) in moves.iter().zip(boards.windows(2)) {
+            if coord.is_some() {
+                let turn = canonical_transform(&pair[0].1);
+                let next = pair[1].1.transforms()[turn].to_compact_string();
+                let booked =
+                    self.positions.entry(key(&pair[0].1, disk)).or_default();
+                if !booked.contains(&next) {
+                    booked.push(next);
+                }
+            }
+            disk = match disk {
+                Disk::Black => Disk::White,
+                Disk::White => Disk::Black,
+            };
+        }
+    }
+}
+
+/// Returns the index of the first of `Board::transforms` that gives the
+/// canonical board.
+fn canonical_transform(board: &Board) -> usize {
+    let transforms = board.transforms();
+    (0..8)
+        .min_by_key(|&index| transforms[index].to_compact_string())
+        .unwrap()
+}
+
+/// Names a position by its canonical board and the side to move.
+fn key(board: &Board, disk: Disk) -> String {
+    let mut key = board.canonical().to_compact_string();
+    key.push(disk.to_char());
+
+    key
+}
+
+// =====================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{BookError, OpeningBook};
+    use crate::board::{Board, Disk};
+    use crate::engine::{Command, Engine, Side};
+    use crate::position::Coordinate;
+    use crate::search;
+    use crate::strategy::{BookStrategy, Strategy};
+    use std::sync::Arc;
+
+    const BOOK: &str = "f5d6c3\n# Parallel\nf5f4e3\n";
+
+    #[test]
+    fn book_moves() {
+        let book = OpeningBook::load(BOOK).unwrap();
+        assert_eq!(book.len(), 4);
+
+        let mut board = Board::new();
+        board.init();
+        let f5 = Coordinate::new('f', 5);
+        assert_eq!(book.book_move(&board, Disk::Black), Some(f5));
+        assert_eq!(book.book_move(&board, Disk::White), None);
+
+        let after_f5 = board.try_move(f5, Disk::Black).unwrap();
+        let d6 = Coordinate::new('d', 6);
+        assert_eq!(book.book_move(&after_f5, Disk::White), Some(d6));
+        // d3 reflects f5 across the a8-h1 diagonal, and c5 reflects d6.
+        let d3 = Coordinate::new('d', 3);
+        let after_d3 = board.try_move(d3, Disk::Black).unwrap();
+        let c5 = Coordinate::new('c', 5);
+        assert_eq!(book.book_move(&after_d3, Disk::White), Some(c5));
+
+        match OpeningBook::load("f5d6\nf5z9\n") {
+            Err(BookError::Transcript(2, _)) => (),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn engine_plays_booked_move() {
+        let book = OpeningBook::load(BOOK).unwrap();
+        let mut strategy = BookStrategy::new(Arc::new(book), 2);
+        let mut engine = Engine::new();
+        engine.action(Command::Init);
+
+        let moves = engine.legal_moves();
+        let board = engine.current_board();
+        let coord = strategy.choose_move(board, Side::Dark, &moves);
+        assert_eq!(coord, Some(Coordinate::new('f', 5)));
+        engine.action(Command::Move(coord.unwrap()));
+        engine.action(Command::Move(Coordinate::new('f', 4)));
+
+        let moves = engine.legal_moves();
+        let board = engine.current_board();
+        let coord = strategy.choose_move(board, Side::Dark, &moves);
+        assert_eq!(coord, Some(Coordinate::new('e', 3)));
+        engine.action(Command::Move(coord.unwrap()));
+
+        // Out of the book, the search takes over.
+        let moves = engine.legal_moves();
+        let board = engine.current_board().clone();
+        let coord = strategy.choose_move(&board, Side::Light, &moves);
+        assert_eq!(coord, search::best_move(&board, Disk::White, 2));
+    }
+}
@@ -1,129 +1,2583 @@
+use crate::ansi;
 use crate::board::Disk;
-use crate::engine::{Command, Engine};
-use crate::position::Coordinate;
-use std::io::{self, Write};
+use crate::book::OpeningBook;
+use crate::engine::{change_turn, Command, Engine, GameStatus, Side};
+use crate::messages::{GameMessage, Lang};
+use crate::position::{Column, Coordinate, Row};
+use crate::search;
+use crate::strategy::{minimax, parse_strategy, AiStrategy, GreedyStrategy};
+use crate::strategy::{HumanStrategy, RandomStrategy, Strategy};
+use crate::timing::{format_minutes, format_seconds, MoveTimer, SystemClock};
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+#[cfg(feature = "line-editing")]
+use rustyline::completion::Completer;
+#[cfg(feature = "line-editing")]
+use rustyline::error::ReadlineError;
+#[cfg(feature = "line-editing")]
+use rustyline::highlight::Highlighter;
+#[cfg(feature = "line-editing")]
+use rustyline::hint::Hinter;
+#[cfg(feature = "line-editing")]
+use rustyline::validate::Validator;
+#[cfg(feature = "line-editing")]
+use rustyline::{Context, Editor, Helper};
+use std::cell::RefCell;
+use std::cmp::Ordering;
+#[cfg(feature = "line-editing")]
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::Path;
+#[cfg(feature = "line-editing")]
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
 
-pub fn run() -> Result<(), &'static str> {
-    let mut buffer = String::with_capacity(4096);
+const COMMANDS: [&str; 27] = [
+    "ai", "board", "draw", "eval", "export", "help", "hint", "hints",
+    "history", "init", "lang", "load", "move", "moves", "name", "perft",
+    "quit", "redo", "resign", "save", "score", "selfplay", "show", "symbols",
+    "times", "undo", "vs",
+];
+
+/// Short names for the common commands.
+const ALIASES: [(&str, &str); 6] = [
+    ("m", "move"),
+    ("u", "undo"),
+    ("r", "redo"),
+    ("q", "quit"),
+    ("h", "help"),
+    ("n", "init"),
+];
+
+const AI_USAGE: &str =
+    "ai [black|white] off|random [seed]|greedy|minimax {depth}";
+const SELFPLAY_USAGE: &str =
+    "selfplay {games} [black] [white] [seed] [--verbose]";
+
+const AUTOSAVE_FILE: &str = "othello-autosave.txt";
+
+#[cfg(feature = "line-editing")]
+const HISTORY_FILE: &str = ".othello_history";
+
+const WATCH_DEPTH: u8 = 3;
+/// Default and largest depth of `eval`, which searches every move.
+const EVAL_DEPTH: u8 = 3;
+const MAX_EVAL_DEPTH: u8 = 5;
+/// The deepest `perft`, which takes some seconds from the start.
+const MAX_PERFT_DEPTH: u8 = 9;
+
+pub fn run(
+    engine: Engine,
+    config: RenderConfig,
+    black: Box<dyn Strategy>,
+    white: Box<dyn Strategy>,
+    seed: Option<u64>,
+    book: Option<Arc<OpeningBook>>,
+) -> Result<(), &'static str> {
+    let completer = CommandCompleter::new();
+    let legal_moves = Rc::clone(&completer.legal_moves);
+    let mut reader = LineReader::new(completer);
+    let mut game = Game::new();
+    game.engine = engine;
+    game.config = config;
+    game.black = black;
+    game.white = white;
+    if let Some(seed) = seed {
+        game.rng = SmallRng::seed_from_u64(seed);
+    }
+    game.book = book;
+    game.engine.set_lang(Lang::from_env());
+    game.engine.action(Command::Init);
+
+    command_loop(&mut game, &mut reader, &legal_moves, &mut io::stdout())
+}
+
+/// Runs the commands in a script file on a new game and returns
+/// everything written, stopping at `quit` or the end of the file.
+pub fn run_script(path: &Path) -> Result<String, ScriptError> {
+    let mut script = BufReader::new(fs::File::open(path)?);
     let mut game = Game::new();
     game.engine.action(Command::Init);
-    game.render();
 
-    loop {
-        print!("Command? ");
-        if io::stdout().flush().is_err() {
-            return Err("Failed to flush in stdout");
-        }
-        if io::stdin().read_line(&mut buffer).is_err() {
-            return Err("Failed to read input");
+    let mut out = Vec::new();
+    game.render(&mut out)?;
+    game.run_script(&mut script, &mut out)?;
+
+    Ok(String::from_utf8_lossy(&out).into_owned())
+}
+
+#[derive(Debug)]
+pub enum ScriptError {
+    Io(io::Error),
+    UnknownCommand(usize, String),
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScriptError::Io(err) => write!(f, "{}", err),
+            ScriptError::UnknownCommand(line, command) => {
+                write!(f, "line {}: unknown command {}", line, command)
+            }
         }
+    }
+}
 
-        let mut iter = buffer.trim().split_whitespace();
-        if let Some(command) = iter.next() {
-            if command == "quit" {
+impl From<io::Error> for ScriptError {
+    fn from(err: io::Error) -> ScriptError {
+        ScriptError::Io(err)
+    }
+}
+
+/// Lets the computer play both sides, pausing for `delay` before each
+/// move, until the game is over.
+pub fn watch(
+    engine: Engine,
+    config: RenderConfig,
+    delay: Duration,
+) -> Result<(), &'static str> {
+    let mut game = Game::new();
+    game.engine = engine;
+    game.config = config;
+    game.black = Box::new(AiStrategy(WATCH_DEPTH));
+    game.white = Box::new(AiStrategy(WATCH_DEPTH));
+    game.delay = delay;
+    game.engine.action(Command::Init);
+
+    let mut out = io::stdout();
+    if game.render(&mut out).is_err()
+        || game.play_computer_moves(&mut out).is_err()
+    {
+        return Err("Failed to write output");
+    }
+
+    Ok(())
+}
+
+/// Runs the `selfplay` command with the given arguments, such as
+/// `["100", "greedy", "random"]`, and prints the summary. `seed` stands
+/// in for a missing seed argument.
+pub fn selfplay(
+    config: RenderConfig,
+    args: &[&str],
+    seed: Option<u64>,
+) -> Result<(), &'static str> {
+    let mut game = Game::new();
+    game.config = config;
+    if let Some(seed) = seed {
+        game.rng = SmallRng::seed_from_u64(seed);
+    }
+    let line = format!("selfplay {}", args.join(" "));
+    match game.execute(&line, &mut io::stdout()) {
+        Ok(_) => Ok(()),
+        Err(_) => Err("Failed to write output"),
+    }
+}
+
+/// Reads and runs commands until the player quits or the input ends.
+fn command_loop(
+    game: &mut Game,
+    reader: &mut LineReader,
+    legal_moves: &RefCell<Vec<Coordinate>>,
+    out: &mut dyn Write,
+) -> Result<(), &'static str> {
+    if game.render(out).is_err() || game.play_computer_moves(out).is_err() {
+        return Err("Failed to write output");
+    }
+
+    loop {
+        *legal_moves.borrow_mut() = game.engine.legal_moves();
+        game.timer.start();
+        let buffer = match reader.read_line(&game.prompt(), out)? {
+            Some(line) => line,
+            None if reader.is_interactive()
+                && game.confirm.is_none()
+                && game.in_progress() =>
+            {
+                "quit".to_string()
+            }
+            None => {
                 game.engine.action(Command::Quit);
                 break;
-            } else if command == "help" {
-                print_help();
-                game.render();
-            } else if command == "init" {
-                game.engine.action(Command::Init);
-                game.render();
-            } else if command == "undo" {
-                game.engine.action(Command::Undo);
-                game.render();
-            } else if command == "move" {
-                match parse_coordinate(iter.next()) {
-                    Ok((col, row)) => {
-                        let coord = Coordinate::new(col, row);
-                        game.engine.action(Command::Move(coord));
-                        game.render();
+            }
+        };
+
+        match game.execute(&buffer, out) {
+            Ok(true) => (),
+            Ok(false) => break,
+            Err(_) => return Err("Failed to write output"),
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads commands with line editing and history when stdin is a
+/// terminal, and plainly otherwise, such as when input is piped in.
+enum LineReader {
+    #[cfg(feature = "line-editing")]
+    Editor(Editor<CommandCompleter>),
+    Plain(Box<dyn BufRead>),
+}
+
+impl LineReader {
+    #[cfg(feature = "line-editing")]
+    fn new(completer: CommandCompleter) -> LineReader {
+        if atty::is(atty::Stream::Stdin) {
+            let mut editor = Editor::new();
+            editor.set_helper(Some(completer));
+            if let Some(path) = history_path() {
+                let _ = editor.load_history(&path);
+            }
+            LineReader::Editor(editor)
+        } else {
+            LineReader::Plain(Box::new(io::BufReader::new(io::stdin())))
+        }
+    }
+
+    #[cfg(not(feature = "line-editing"))]
+    fn new(_completer: CommandCompleter) -> LineReader {
+        LineReader::Plain(Box::new(io::BufReader::new(io::stdin())))
+    }
+
+    /// Tells whether the player types at a terminal, who can still answer
+    /// a question after pressing Ctrl-D.
+    fn is_interactive(&self) -> bool {
+        match self {
+            #[cfg(feature = "line-editing")]
+            LineReader::Editor(_) => true,
+            LineReader::Plain(_) => false,
+        }
+    }
+
+    /// Returns `None` at the end of input, and an empty line when Ctrl-C
+    /// cancels the line being typed.
+    fn read_line(
+        &mut self,
+        prompt: &str,
+        out: &mut dyn Write,
+    ) -> Result<Option<String>, &'static str> {
+        match self {
+            #[cfg(feature = "line-editing")]
+            LineReader::Editor(editor) => match editor.readline(prompt) {
+                Ok(line) => {
+                    editor.add_history_entry(line.as_str());
+                    if let Some(path) = history_path() {
+                        let _ = editor.save_history(&path);
                     }
-                    Err(s) => println!("{}", s),
+                    Ok(Some(line))
+                }
+                Err(ReadlineError::Interrupted) => Ok(Some(String::new())),
+                Err(ReadlineError::Eof) => Ok(None),
+                Err(_) => Err("Failed to read input"),
+            },
+            LineReader::Plain(input) => {
+                if write!(out, "{}", prompt).and_then(|_| out.flush()).is_err()
+                {
+                    return Err("Failed to flush output");
+                }
+
+                let mut buffer = String::with_capacity(4096);
+                match input.read_line(&mut buffer) {
+                    Ok(0) => Ok(None),
+                    Ok(_) => Ok(Some(buffer)),
+                    Err(_) => Err("Failed to read input"),
                 }
-            } else {
-                println!("Unknown command: {}", command);
             }
         }
+    }
+}
+
+/// Returns where the command history is kept, `~/.othello_history`.
+#[cfg(feature = "line-editing")]
+fn history_path() -> Option<PathBuf> {
+    env::var_os("HOME").map(|home| PathBuf::from(home).join(HISTORY_FILE))
+}
 
-        buffer.clear();
+/// Completes command names, and the legal moves after `move`.
+struct CommandCompleter {
+    legal_moves: Rc<RefCell<Vec<Coordinate>>>,
+}
+
+impl CommandCompleter {
+    fn new() -> CommandCompleter {
+        CommandCompleter {
+            legal_moves: Rc::new(RefCell::new(Vec::new())),
+        }
     }
 
-    Ok(())
+    #[cfg(feature = "line-editing")]
+    fn candidates(&self, line: &str, pos: usize) -> (usize, Vec<String>) {
+        let line = &line[..pos];
+        let start = line.rfind(' ').map_or(0, |index| index + 1);
+        let word = &line[start..];
+
+        let mut words = line[..start].split_whitespace();
+        let candidates = match (words.next(), words.next()) {
+            (None, _) => COMMANDS
+                .iter()
+                .filter(|command| command.starts_with(word))
+                .map(|command| command.to_string())
+                .collect(),
+            (Some("move"), None) => self
+                .legal_moves
+                .borrow()
+                .iter()
+                .map(|coord| coord.to_string())
+                .filter(|coord| coord.starts_with(word))
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        (start, candidates)
+    }
 }
 
-fn print_help() {
-    let output = "\n\
-Command:
-  quit => Quit the program.
-  help => Show this help message.
-  init => Start a new game.
-  undo => Go back to previous move.
-  move {coordinate} => Press disk at the position with coordinate,
-      such as `move a1`, `move c4` or `move h8`.
-";
-    println!("{}", output);
+#[cfg(feature = "line-editing")]
+impl Completer for CommandCompleter {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        Ok(self.candidates(line, pos))
+    }
+}
+
+#[cfg(feature = "line-editing")]
+impl Hinter for CommandCompleter {
+    type Hint = String;
+}
+
+#[cfg(feature = "line-editing")]
+impl Highlighter for CommandCompleter {}
+
+#[cfg(feature = "line-editing")]
+impl Validator for CommandCompleter {}
+
+#[cfg(feature = "line-editing")]
+impl Helper for CommandCompleter {}
+
+fn print_help(out: &mut dyn Write, lang: Lang) -> io::Result<()> {
+    writeln!(out, "{}", lang.help())
+}
+
+/// Turns an alias such as `m` or `q!` into the command it stands for,
+/// leaving other words as they are.
+fn expand_alias(command: &str) -> String {
+    let name = command.trim_end_matches('!');
+    let bang = &command[name.len()..];
+    match ALIASES.iter().find(|&&(alias, _)| alias == name) {
+        Some(&(_, full)) => format!("{}{}", full, bang),
+        None => command.to_string(),
+    }
 }
 
-fn parse_coordinate(
+/// Returns the command closest to an unknown `word`, when it is at most
+/// two edits away and `word` isn't so short that anything would match.
+fn suggest_command(word: &str) -> Option<&'static str> {
+    let length = word.chars().count();
+    COMMANDS
+        .iter()
+        .map(|&command| (edit_distance(word, command), command))
+        .filter(|&(distance, _)| distance <= 2 && distance < length)
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, command)| command)
+}
+
+/// Returns the Levenshtein distance: the fewest insertions, deletions and
+/// substitutions of characters turning `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = previous[j] + if ca == cb { 0 } else { 1 };
+            let deletion = previous[j + 1] + 1;
+            let insertion = current[j] + 1;
+            current.push(substitution.min(deletion).min(insertion));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CoordinateInputError {
+    MissingInput,
+    TooShort(String),
+    InvalidColumn(char),
+    InvalidRow(String),
+    OutOfRange(char, usize),
+}
+
+impl fmt::Display for CoordinateInputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CoordinateInputError::MissingInput => {
+                write!(f, "Missing coordinate, such as `move c4`")
+            }
+            CoordinateInputError::TooShort(input) => {
+                write!(f, "Coordinate too short: {}", input)
+            }
+            CoordinateInputError::InvalidColumn(col) => {
+                write!(f, "Invalid column: {}", col)
+            }
+            CoordinateInputError::InvalidRow(row) => {
+                write!(f, "Invalid row: {}", row)
+            }
+            CoordinateInputError::OutOfRange(col, row) => {
+                write!(f, "Out of the board: {}{}", col, row)
+            }
+        }
+    }
+}
+
+/// Parses the arguments of the `ai` command, such as `greedy` or
+/// `minimax 4`. A random player without a seed is given `seed`, and a
+/// minimax player plays from `book` when there is one.
+fn parse_ai(
+    words: &[&str],
+    seed: u64,
+    book: Option<&Arc<OpeningBook>>,
+) -> Option<Box<dyn Strategy>> {
+    match words {
+        ["off"] => Some(Box::new(HumanStrategy)),
+        ["random"] => Some(Box::new(RandomStrategy::with_seed(seed))),
+        ["random", seed] => {
+            let seed = seed.parse().ok()?;
+            Some(Box::new(RandomStrategy::with_seed(seed)))
+        }
+        ["greedy"] => Some(Box::new(GreedyStrategy)),
+        ["minimax", depth] => match depth.parse() {
+            Ok(depth) if depth > 0 => Some(minimax(depth, book)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Parses a player like `parse_strategy`, seeding a random one.
+fn seeded_strategy(
+    name: &str,
+    seed: u64,
+    book: Option<&Arc<OpeningBook>>,
+) -> Option<Box<dyn Strategy>> {
+    match name {
+        "random" => Some(Box::new(RandomStrategy::with_seed(seed))),
+        _ => parse_strategy(name, book),
+    }
+}
+
+/// Parses a player for `selfplay` like `seeded_strategy`, refusing
+/// humans.
+fn selfplay_strategy(name: &str, seed: u64) -> Option<Box<dyn Strategy>> {
+    match name {
+        "human" => None,
+        _ => seeded_strategy(name, seed, None),
+    }
+}
+
+pub fn parse_coordinate(
     coord: Option<&str>,
-) -> Result<(char, usize), &'static str> {
-    if let Some(coord) = coord {
-        if coord.len() >= 2 {
-            let col = coord.as_bytes()[0] as char;
-            let row = &coord.as_bytes()[1..];
-            if let Ok(row) = std::str::from_utf8(&row) {
-                if let Ok(row) = row.parse::<usize>() {
-                    if 'a' <= col && col <= 'h' && 1 <= row && row <= 8 {
-                        return Ok((col, row));
-                    }
-                }
+) -> Result<Coordinate, CoordinateInputError> {
+    let coord = match coord {
+        Some(coord) => coord,
+        None => return Err(CoordinateInputError::MissingInput),
+    };
+
+    let mut chars = coord.chars();
+    let col = match chars.next() {
+        Some(col) if coord.chars().count() >= 2 => col,
+        _ => return Err(CoordinateInputError::TooShort(coord.to_string())),
+    };
+    if !col.is_ascii_alphabetic() {
+        return Err(CoordinateInputError::InvalidColumn(col));
+    }
+
+    let row = chars.as_str();
+    let row = match row.parse::<usize>() {
+        Ok(row) => row,
+        Err(_) => {
+            return Err(CoordinateInputError::InvalidRow(row.to_string()))
+        }
+    };
+
+    match Coordinate::try_new(col, row) {
+        Some(coord) => Ok(coord),
+        None => Err(CoordinateInputError::OutOfRange(col, row)),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RenderConfig {
+    pub black: char,
+    pub white: char,
+    pub empty: char,
+    pub color: bool,
+    pub json: bool,
+    pub hints: bool,
+}
+
+impl RenderConfig {
+    /// Parses three symbols for black, white and empty squares, such as
+    /// `xo.` or `# O .`.
+    pub fn parse(symbols: &str) -> Option<RenderConfig> {
+        let mut chars = symbols.chars().filter(|c| !c.is_whitespace());
+        let config = RenderConfig {
+            black: chars.next()?,
+            white: chars.next()?,
+            empty: chars.next()?,
+            color: false,
+            json: false,
+            hints: false,
+        };
+
+        let distinct = config.black != config.white
+            && config.black != config.empty
+            && config.white != config.empty;
+        match chars.next() {
+            None if distinct => Some(config),
+            _ => None,
+        }
+    }
+
+    /// Returns true unless the symbols were changed from `x`, `o` and `.`.
+    fn is_default(&self) -> bool {
+        let default = RenderConfig::default();
+        (self.black, self.white, self.empty)
+            == (default.black, default.white, default.empty)
+    }
+}
+
+impl Default for RenderConfig {
+    fn default() -> RenderConfig {
+        RenderConfig {
+            black: 'x',
+            white: 'o',
+            empty: '.',
+            color: false,
+            json: false,
+            hints: false,
+        }
+    }
+}
+
+/// Describes a move like "Black played f5, flipping 1 disk (e5)", listing
+/// at most six of the flipped squares.
+fn describe_move(
+    name: &str,
+    coord: Coordinate,
+    flipped: &[Coordinate],
+) -> String {
+    let mut squares: Vec<String> = flipped
+        .iter()
+        .take(6)
+        .map(|coord| coord.to_string())
+        .collect();
+    if flipped.len() > 6 {
+        squares.push("…".to_string());
+    }
+    let disks = if flipped.len() == 1 { "disk" } else { "disks" };
+
+    format!(
+        "{} played {}, flipping {} {} ({})",
+        name,
+        coord,
+        flipped.len(),
+        disks,
+        squares.join(", ")
+    )
+}
+
+/// Draws each value as one of eight bars, from the lowest value to the
+/// highest, or as middle bars when they are all the same.
+fn sparkline(values: &[i32]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let min = values.iter().copied().min().unwrap_or(0);
+    let max = values.iter().copied().max().unwrap_or(0);
+    values
+        .iter()
+        .map(|&value| match max - min {
+            0 => BARS[3],
+            range => BARS[((value - min) * 7 / range) as usize],
+        })
+        .collect()
+}
+
+/// Splits a save file into the players' names and the moves.
+fn parse_save_file(text: &str) -> (Vec<(Side, String)>, String) {
+    let mut names = Vec::new();
+    let mut transcript = String::new();
+    for line in text.lines() {
+        let mut words = line.split_whitespace();
+        if words.next() != Some("name") {
+            transcript += line;
+            transcript += "\n";
+            continue;
+        }
+
+        let side = match words.next() {
+            Some("black") => Side::Dark,
+            Some("white") => Side::Light,
+            _ => continue,
+        };
+        names.push((side, words.collect::<Vec<&str>>().join(" ")));
+    }
+
+    (names, transcript)
+}
+
+/// Quotes a string for JSON, escaping quotes, backslashes and control
+/// characters.
+fn json_string(s: &str) -> String {
+    let mut output = String::with_capacity(s.len() + 2);
+    output.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => output += "\\\"",
+            '\\' => output += "\\\\",
+            '\n' => output += "\\n",
+            c if (c as u32) < 0x20 => {
+                output += &format!("\\u{:04x}", c as u32)
             }
+            c => output.push(c),
         }
     }
+    output.push('"');
 
-    Err("Invalid coordinate")
+    output
 }
 
+/// Lists moves as numbered pairs, such as `1. f5 d6  2. c3 --`, wrapped
+/// to 80 columns. A `|` is put before the move at `cursor` when it is
+/// not the last.
+fn format_history(moves: &[Option<Coordinate>], cursor: usize) -> String {
+    let mut pairs = Vec::new();
+    for (i, pair) in moves.chunks(2).enumerate() {
+        let mut text = format!("{}.", i + 1);
+        for (j, coord) in pair.iter().enumerate() {
+            if i * 2 + j == cursor {
+                text += " |";
+            }
+            match coord {
+                Some(coord) => text += &format!(" {}", coord),
+                None => text += " --",
+            }
+        }
+        pairs.push(text);
+    }
+
+    let mut output = String::new();
+    let mut line = String::new();
+    for pair in pairs {
+        if !line.is_empty() && line.len() + 2 + pair.len() > 80 {
+            output += &line;
+            output += "\n";
+            line.clear();
+        }
+        if !line.is_empty() {
+            line += "  ";
+        }
+        line += &pair;
+    }
+    if !line.is_empty() {
+        output += &line;
+        output += "\n";
+    }
+
+    output
+}
+
+// ---------------------------------------------------------------------
+
 pub struct Game {
     engine: Engine,
+    config: RenderConfig,
+    black: Box<dyn Strategy>,
+    white: Box<dyn Strategy>,
+    delay: Duration,
+    confirm: Option<Command>,
+    /// Seeds the random players, so that `--seed` replays their moves.
+    rng: SmallRng,
+    timer: MoveTimer,
+    /// Opening book for the minimax players made by `ai` and `vs`.
+    book: Option<Arc<OpeningBook>>,
 }
 
 impl Game {
     pub fn new() -> Game {
         Game {
             engine: Engine::new(),
+            config: RenderConfig::default(),
+            black: Box::new(HumanStrategy),
+            white: Box::new(HumanStrategy),
+            delay: Duration::from_millis(0),
+            confirm: None,
+            rng: SmallRng::from_entropy(),
+            timer: MoveTimer::new(Box::new(SystemClock::new())),
+            book: None,
         }
     }
 
-    pub fn render(&self) {
-        let mut output = String::with_capacity(1024);
+    /// Draws the board with the last disk placed highlighted, or in
+    /// brackets without colors.
+    pub fn grid(&self) -> String {
         let board = self.engine.current_board();
-        let mut black = 0;
-        let mut white = 0;
-
-        output += "   a  b  c  d  e  f  g  h\n";
-        for row in 1..=8 {
-            output += format!("{} ", row).as_str();
-            for col in 'a'..='h' {
-                let coord = Coordinate::new(col, row);
-                let symbol = match board.get_disk(coord) {
-                    None => '.',
-                    Some(Disk::Black) => {
-                        black += 1;
-                        'x'
+        let hints = match self.engine.status() {
+            _ if !self.config.hints => Vec::new(),
+            status if status.is_over() => Vec::new(),
+            _ => self.engine.legal_moves(),
+        };
+        let last_move = self.engine.move_history().into_iter().flatten();
+        let last_move = last_move.last();
+        if self.config.color {
+            return ansi::render_grid_with_hints(board, last_move, &hints);
+        }
+
+        let RenderConfig {
+            black,
+            white,
+            empty,
+            ..
+        } = self.config;
+        board.render_grid_marked(black, white, empty, &hints, last_move)
+    }
+
+    /// Runs one command line, writing any output to `out`. Returns
+    /// `false` once the player quits.
+    pub fn execute(
+        &mut self,
+        line: &str,
+        out: &mut dyn Write,
+    ) -> io::Result<bool> {
+        if let Some(command) = self.confirm.take() {
+            let answer = line.trim().to_lowercase();
+            if answer != "y" && answer != "yes" {
+                writeln!(out, "Cancelled.")?;
+                return Ok(true);
+            }
+            return self.execute(
+                match command {
+                    Command::Quit => "quit!",
+                    _ => "init!",
+                },
+                out,
+            );
+        }
+
+        let mut iter = line.trim().split_whitespace();
+        if let Some(command) = iter.next() {
+            let command = expand_alias(command);
+            let command = command.as_str();
+            if (command == "quit" || command == "init") && self.in_progress() {
+                self.confirm = Some(match command {
+                    "quit" => Command::Quit,
+                    _ => Command::Init,
+                });
+                writeln!(out, "Discard the current game? (y/N)")?;
+            } else if command == "quit" || command == "quit!" {
+                self.engine.action(Command::Quit);
+                return Ok(false);
+            } else if command == "help" {
+                print_help(out, self.engine.lang())?;
+            } else if command == "show" || command == "board" {
+                self.render(out)?;
+            } else if command == "init" || command == "init!" {
+                self.engine.action(Command::Init);
+                self.timer.truncate(0);
+                self.render(out)?;
+                self.play_computer_moves(out)?;
+            } else if command == "undo" {
+                match iter.next() {
+                    None => {
+                        self.undo(out)?;
+                        while !self.is_human_turn()
+                            && !self.engine.is_at_root()
+                        {
+                            self.undo(out)?;
+                        }
                     }
-                    Some(Disk::White) => {
-                        white += 1;
-                        'o'
+                    Some(count) => match count.parse::<usize>() {
+                        Ok(count) if count > 0 => {
+                            self.undo_count(count, out)?
+                        }
+                        _ => {
+                            writeln!(out, "Invalid count: {}", count)?;
+                            return Ok(true);
+                        }
+                    },
+                }
+                self.render(out)?;
+                self.play_computer_moves(out)?;
+            } else if command == "ai" {
+                let words: Vec<&str> = iter.collect();
+                let (side, words) = match words.first() {
+                    Some(&"black") => (Side::Dark, &words[1..]),
+                    Some(&"white") => (Side::Light, &words[1..]),
+                    _ => (change_turn(self.engine.turn()), &words[..]),
+                };
+                match parse_ai(words, self.rng.gen(), self.book.as_ref()) {
+                    Some(strategy) => {
+                        writeln!(
+                            out,
+                            "{} is played by {}.",
+                            self.engine.name(side),
+                            words.join(" ")
+                        )?;
+                        match side {
+                            Side::Dark => self.black = strategy,
+                            Side::Light => self.white = strategy,
+                        }
+                        self.play_computer_moves(out)?;
                     }
+                    None => writeln!(out, "Usage: {}", AI_USAGE)?,
+                }
+            } else if command == "redo" {
+                let count = match iter.next() {
+                    None => 1,
+                    Some(count) => match count.parse::<usize>() {
+                        Ok(count) => count,
+                        Err(_) => {
+                            writeln!(out, "Invalid count: {}", count)?;
+                            return Ok(true);
+                        }
+                    },
                 };
-                output += format!(" {} ", symbol).as_str();
+                if self.engine.redo_n(count) > 0 {
+                    // The computer's replies come back with the moves, as
+                    // undo takes them back together.
+                    while !self.is_human_turn()
+                        && !self.engine.redo_moves().is_empty()
+                    {
+                        self.engine.redo_n(1);
+                    }
+                    self.render(out)?;
+                    self.play_computer_moves(out)?;
+                } else {
+                    writeln!(out, "Nothing to redo")?;
+                }
+            } else if command == "vs" {
+                let (first, second) = (self.rng.gen(), self.rng.gen());
+                let book = self.book.as_ref();
+                let black = iter
+                    .next()
+                    .and_then(|name| seeded_strategy(name, first, book));
+                let white = iter
+                    .next()
+                    .and_then(|name| seeded_strategy(name, second, book));
+                match (black, white) {
+                    (Some(black), Some(white)) => {
+                        self.black = black;
+                        self.white = white;
+                        self.play_computer_moves(out)?;
+                    }
+                    _ => writeln!(out, "Usage: vs {{black}} {{white}}")?,
+                }
+            } else if command == "save" {
+                let path = iter.next().unwrap_or(AUTOSAVE_FILE);
+                let plies = self.engine.move_history().len();
+                match fs::write(path, self.save_file()) {
+                    Ok(()) => {
+                        writeln!(out, "Saved {} moves to {}", plies, path)?
+                    }
+                    Err(err) => {
+                        writeln!(out, "Failed to save {}: {}", path, err)?
+                    }
+                }
+            } else if command == "load" {
+                let path = iter.next().unwrap_or(AUTOSAVE_FILE);
+                let loaded = fs::read_to_string(path)
+                    .map_err(|err| err.to_string())
+                    .and_then(|text| {
+                        let (names, transcript) = parse_save_file(&text);
+                        self.engine
+                            .load_transcript(&transcript)
+                            .map_err(|err| err.to_string())?;
+                        for (side, name) in names {
+                            self.engine.set_name(side, &name);
+                        }
+                        Ok(())
+                    });
+                match loaded {
+                    Ok(()) => {
+                        self.timer.truncate(0);
+                        let plies = self.engine.move_history().len();
+                        let message = GameMessage::Loaded(plies);
+                        let status = self.engine.status_text();
+                        self.engine.prompt =
+                            self.engine.lang().prompt(Some(message), &status);
+                        self.render(out)?;
+                        self.play_computer_moves(out)?;
+                    }
+                    Err(err) => {
+                        writeln!(out, "Failed to load {}: {}", path, err)?
+                    }
+                }
+            } else if command == "name" {
+                let side = match iter.next() {
+                    Some("black") => Some(Side::Dark),
+                    Some("white") => Some(Side::Light),
+                    _ => None,
+                };
+                let name = iter.collect::<Vec<&str>>().join(" ");
+                match side {
+                    Some(side) if !name.is_empty() => {
+                        self.engine.set_name(side, &name);
+                        self.render(out)?;
+                    }
+                    _ => writeln!(out, "Usage: name black|white {{name}}")?,
+                }
+            } else if command == "hints" {
+                let hints = match iter.next() {
+                    Some("on") => Some(true),
+                    Some("off") => Some(false),
+                    _ => None,
+                };
+                match hints {
+                    Some(hints) => {
+                        self.config.hints = hints;
+                        self.render(out)?;
+                    }
+                    None => writeln!(out, "Usage: hints on|off")?,
+                }
+            } else if command == "lang" {
+                match iter.next().and_then(Lang::parse) {
+                    Some(lang) => {
+                        self.engine.set_lang(lang);
+                        self.render(out)?;
+                    }
+                    None => writeln!(out, "Usage: lang en|ja")?,
+                }
+            } else if command == "export" {
+                match iter.next() {
+                    Some("art") => write!(out, "{}", self.export_ascii_art())?,
+                    _ => self.export(out)?,
+                }
+            } else if command == "hint" {
+                self.print_hint(out)?;
+            } else if command == "eval" {
+                let depth = match iter.next() {
+                    None => EVAL_DEPTH,
+                    Some(depth) => match depth.parse::<u8>() {
+                        Ok(depth) if depth > 0 => depth,
+                        _ => {
+                            return writeln!(out, "Usage: eval [depth]")
+                                .map(|_| true)
+                        }
+                    },
+                };
+                self.print_eval(depth, out)?;
+            } else if command == "perft" {
+                match iter.next().map(str::parse::<u8>) {
+                    Some(Ok(depth)) if depth > MAX_PERFT_DEPTH => writeln!(
+                        out,
+                        "Depth {} would take too long; try {} or less.",
+                        depth, MAX_PERFT_DEPTH
+                    )?,
+                    Some(Ok(depth)) if depth > 0 => {
+                        self.print_perft(depth, out)?
+                    }
+                    _ => writeln!(out, "Usage: perft {{depth}}")?,
+                }
+            } else if command == "score" {
+                self.print_score(out)?;
+            } else if command == "selfplay" {
+                let words: Vec<&str> = iter.collect();
+                self.selfplay(&words, out)?;
+            } else if command == "history" || command == "moves" {
+                let played = self.engine.move_history();
+                let cursor = played.len();
+                let moves = [played, self.engine.redo_moves()].concat();
+                write!(out, "{}", format_history(&moves, cursor))?;
+            } else if command == "symbols" {
+                let symbols: String = iter.collect();
+                match RenderConfig::parse(&symbols) {
+                    Some(config) => {
+                        self.config = RenderConfig {
+                            color: self.config.color,
+                            json: self.config.json,
+                            hints: self.config.hints,
+                            ..config
+                        };
+                        self.render(out)?;
+                    }
+                    None => writeln!(out, "Invalid symbols")?,
+                }
+            } else if command == "times" {
+                self.print_times(out)?;
+            } else if command == "resign" {
+                if self.engine.status().is_over() {
+                    writeln!(out, "The game is over.")?;
+                } else {
+                    let side = self.engine.turn();
+                    self.engine.action(Command::Resign(side));
+                    self.render(out)?;
+                }
+            } else if command == "draw" {
+                let opponent = change_turn(self.engine.turn());
+                if self.engine.status().is_over() {
+                    writeln!(out, "The game is over.")?;
+                } else if self.engine.draw_offer() == Some(opponent) {
+                    self.engine.action(Command::AcceptDraw);
+                    self.render(out)?;
+                } else {
+                    self.engine.action(Command::OfferDraw);
+                    writeln!(out, "{}", self.engine.prompt)?;
+                }
+            } else if command == "move" {
+                if !self.is_human_turn() && !self.engine.status().is_over() {
+                    let name = self.engine.name(self.engine.turn());
+                    writeln!(out, "{} is played by the computer.", name)?;
+                    return Ok(true);
+                }
+                match parse_coordinate(iter.next()) {
+                    Ok(coord) => {
+                        let side = self.engine.turn();
+                        let flipped = self
+                            .engine
+                            .current_board()
+                            .flipped_squares(coord, side.to_disk());
+                        let plies = self.engine.move_history().len();
+                        self.engine.action(Command::Move(coord));
+                        if self.engine.move_history().len() == plies {
+                            writeln!(out, "{}", self.engine.prompt)?;
+                            return Ok(true);
+                        }
+                        self.timer.record(plies, side, coord);
+                        self.report_move(out, side, coord, &flipped)?;
+                        self.render(out)?;
+                        self.play_computer_moves(out)?;
+                    }
+                    Err(err) => writeln!(out, "{}", err)?,
+                }
+            } else {
+                match suggest_command(command) {
+                    Some(suggestion) => writeln!(
+                        out,
+                        "Unknown command '{}' — did you mean '{}'?",
+                        command, suggestion
+                    )?,
+                    None => writeln!(out, "Unknown command: {}", command)?,
+                }
             }
-            output += "\n";
         }
-        println!("{}", output);
-        println!("Black={}, White={}", black, white);
-        println!("{}", self.engine.prompt);
+
+        Ok(true)
+    }
+
+    /// Runs every line of a script until it ends or quits, giving up at
+    /// the first unknown command.
+    pub fn run_script(
+        &mut self,
+        script: &mut dyn BufRead,
+        out: &mut dyn Write,
+    ) -> Result<(), ScriptError> {
+        for (index, line) in script.lines().enumerate() {
+            let line = line?;
+            let command = line.split_whitespace().next();
+            if let (Some(command), None) = (command, &self.confirm) {
+                let command = expand_alias(command.trim_end_matches('!'));
+                if !COMMANDS.contains(&command.as_str()) {
+                    let command = command.to_string();
+                    return Err(ScriptError::UnknownCommand(
+                        index + 1,
+                        command,
+                    ));
+                }
+            }
+            if !self.execute(&line, out)? {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Tells whether a move has been played in a game that isn't over,
+    /// which `quit` and `init` ask before throwing away.
+    fn in_progress(&self) -> bool {
+        !self.engine.status().is_over()
+            && !self.engine.move_history().is_empty()
+    }
+
+    fn is_human_turn(&self) -> bool {
+        match self.engine.turn() {
+            Side::Dark => self.black.is_human(),
+            Side::Light => self.white.is_human(),
+        }
+    }
+
+    /// Lets computer players move until it is a human's turn or the game
+    /// is over.
+    pub fn play_computer_moves(
+        &mut self,
+        out: &mut dyn Write,
+    ) -> io::Result<()> {
+        loop {
+            if self.engine.status().is_over() {
+                return Ok(());
+            }
+
+            let side = self.engine.turn();
+            let strategy = match side {
+                Side::Dark => &mut self.black,
+                Side::Light => &mut self.white,
+            };
+            if strategy.is_human() {
+                return Ok(());
+            }
+            if self.delay > Duration::from_millis(0) {
+                out.flush()?;
+                thread::sleep(self.delay);
+            }
+
+            let board = self.engine.current_board();
+            let moves = self.engine.legal_moves();
+            let plies = self.engine.move_history().len();
+            match strategy.choose_move(board, side, &moves) {
+                Some(coord) => {
+                    let flipped = board.flipped_squares(coord, side.to_disk());
+                    self.engine.action(Command::Move(coord));
+                    self.report_move(out, side, coord, &flipped)?;
+                }
+                None => self.engine.action(Command::Pass),
+            }
+            if self.engine.move_history().len() == plies {
+                return writeln!(out, "{}", self.engine.prompt);
+            }
+            self.render(out)?;
+        }
+    }
+
+    /// Says who played `coord` and what it flipped, except in JSON mode.
+    fn report_move(
+        &self,
+        out: &mut dyn Write,
+        side: Side,
+        coord: Coordinate,
+        flipped: &[Coordinate],
+    ) -> io::Result<()> {
+        if self.config.json {
+            return Ok(());
+        }
+
+        let name = self.engine.name(side);
+        writeln!(out, "{}", describe_move(name, coord, flipped))
+    }
+
+    /// Takes back one ply, saying which move it was.
+    fn undo(&mut self, out: &mut dyn Write) -> io::Result<()> {
+        let played = self.engine.move_history();
+        self.engine.action(Command::Undo);
+        if self.config.json || self.engine.move_history().len() == played.len()
+        {
+            return Ok(());
+        }
+
+        let name = self.engine.name(self.engine.turn());
+        match played.last() {
+            Some(Some(coord)) => {
+                writeln!(out, "Took back {}'s {}.", name, coord)
+            }
+            _ => writeln!(out, "Took back {}'s pass.", name),
+        }
+    }
+
+    /// Takes back `count` plies, and more while the computer is to move,
+    /// then says how many were taken back. Stopped at the start on the
+    /// computer's turn, it is left to the caller to let the computer move.
+    fn undo_count(
+        &mut self,
+        count: usize,
+        out: &mut dyn Write,
+    ) -> io::Result<()> {
+        let mut undone = self.engine.undo_n(count);
+        while !self.is_human_turn() && !self.engine.is_at_root() {
+            undone += self.engine.undo_n(1);
+        }
+        if self.config.json || undone == 0 {
+            return Ok(());
+        }
+
+        let plural = if undone == 1 { "" } else { "s" };
+        if undone < count {
+            writeln!(
+                out,
+                "Undid {} move{} (stopped at the start).",
+                undone, plural
+            )
+        } else {
+            writeln!(out, "Undid {} move{}.", undone, plural)
+        }
+    }
+
+    /// Draws the board and status, or prints them as a line of JSON when
+    /// `json` is set in the config.
+    pub fn render(&self, out: &mut dyn Write) -> io::Result<()> {
+        write!(out, "{}", self.render_to_string())
+    }
+
+    /// Returns what `render` prints, ending in a newline.
+    pub fn render_to_string(&self) -> String {
+        if self.config.json {
+            return format!("{}\n", self.to_json());
+        }
+
+        let board = self.engine.current_board();
+        let black = board.count(Disk::Black);
+        let white = board.count(Disk::White);
+
+        let counts = if self.config.is_default() || self.config.color {
+            format!("Black={}, White={}", black, white)
+        } else {
+            let RenderConfig {
+                black: b, white: w, ..
+            } = self.config;
+            format!("Black ({})={}, White ({})={}", b, black, w, white)
+        };
+        format!(
+            "{}\n{}\nCorners: Black {}, White {}\nMoves: {}, Passes: {}\n\
+             Move {}: {}{}\n",
+            self.grid(),
+            counts,
+            board.corners_held(Disk::Black),
+            board.corners_held(Disk::White),
+            self.engine.count_total_moves_played(),
+            self.engine.count_total_passes(),
+            self.engine.move_number(),
+            self.engine.prompt,
+            self.time_note()
+        )
+    }
+
+    /// Returns how long the last move took and its side's total, when
+    /// the last ply was timed at the prompt.
+    fn time_note(&self) -> String {
+        let plies = self.engine.move_history().len();
+        match self.timer.moves(plies).last() {
+            Some(timed) if timed.ply + 1 == plies => format!(
+                " (this move {}, {} total {})",
+                format_seconds(timed.time),
+                self.engine.name(timed.side),
+                format_minutes(self.timer.total(timed.side, plies))
+            ),
+            _ => String::new(),
+        }
+    }
+
+    /// Lists the time taken by each move played at the prompt, then the
+    /// totals by side.
+    fn print_times(&self, out: &mut dyn Write) -> io::Result<()> {
+        let plies = self.engine.move_history().len();
+        let moves = self.timer.moves(plies);
+        if moves.is_empty() {
+            return writeln!(out, "No moves timed yet.");
+        }
+
+        for timed in &moves {
+            writeln!(
+                out,
+                "{:>3}. {} {} {}",
+                timed.ply + 1,
+                self.engine.name(timed.side),
+                timed.coord,
+                format_seconds(timed.time)
+            )?;
+        }
+        writeln!(
+            out,
+            "{} total {}, {} total {}",
+            self.engine.name(Side::Dark),
+            format_minutes(self.timer.total(Side::Dark, plies)),
+            self.engine.name(Side::Light),
+            format_minutes(self.timer.total(Side::Light, plies))
+        )
+    }
+
+    /// Returns the line editor prompt, naming the player to move once
+    /// they have been given a name.
+    fn prompt(&self) -> String {
+        let side = self.engine.turn();
+        let lang = self.engine.lang();
+        if !self.engine.is_named(side) {
+            return lang.command_prompt();
+        }
+
+        let color = side.label().to_lowercase();
+        let name = self.engine.name(side);
+        format!("{} ({}) — {}", name, color, lang.command_prompt())
+    }
+
+    /// Returns the contents of a save file: a `name` line for each player
+    /// that has been named, then the moves.
+    fn save_file(&self) -> String {
+        let mut text = String::new();
+        for &(side, color) in &[(Side::Dark, "black"), (Side::Light, "white")]
+        {
+            if self.engine.is_named(side) {
+                let name = self.engine.name(side);
+                text += &format!("name {} {}\n", color, name);
+            }
+        }
+
+        text + &self.engine.transcript() + "\n"
+    }
+
+    /// Describes the position as a JSON object on a single line.
+    fn to_json(&self) -> String {
+        let board = self.engine.current_board();
+        let (to_move, game_over) = match self.engine.status() {
+            status if status.is_over() => ("null".to_string(), true),
+            _ => {
+                let side = self.engine.turn().label().to_lowercase();
+                (json_string(&side), false)
+            }
+        };
+        let moves: Vec<String> = self
+            .engine
+            .legal_moves()
+            .iter()
+            .map(|coord| json_string(&coord.to_string()))
+            .collect();
+
+        format!(
+            "{{\"board\":{},\"to_move\":{},\"legal_moves\":[{}],\
+             \"black\":{},\"white\":{},\"game_over\":{},\"message\":{}}}",
+            json_string(&board.to_compact_string()),
+            to_move,
+            moves.join(","),
+            board.count(Disk::Black),
+            board.count(Disk::White),
+            game_over,
+            json_string(&self.engine.prompt)
+        )
+    }
+
+    /// Prints the board in a form that can be pasted elsewhere and loaded
+    /// again with `--position` and `--turn`.
+    fn export(&self, out: &mut dyn Write) -> io::Result<()> {
+        let board = self.engine.current_board();
+        write!(out, "{}", board.render_grid())?;
+        writeln!(out, "Position: {}", board.to_compact_string())?;
+        match self.engine.status() {
+            status if status.is_over() => writeln!(out, "Game over"),
+            _ => writeln!(out, "{} to move", self.engine.turn()),
+        }
+    }
+
+    /// Draws the board with box-drawing characters, followed by the score
+    /// and the status, so that it reads well in any fixed-width font.
+    pub fn export_ascii_art(&self) -> String {
+        let board = self.engine.current_board();
+        let border = |left, middle, right| {
+            format!("  {}{}{}\n", left, ["───"; 8].join(middle), right)
+        };
+
+        let mut art = String::with_capacity(1024);
+        art += "    a   b   c   d   e   f   g   h\n";
+        art += &border("┌", "┬", "┐");
+        for row in Row::iter() {
+            if row.to_u8() > 0 {
+                art += &border("├", "┼", "┤");
+            }
+            art += &format!("{} │", row);
+            for col in Column::iter() {
+                let symbol =
+                    match board.get_disk(Coordinate::from_parts(col, row)) {
+                        None => ' ',
+                        Some(Disk::Black) => self.config.black,
+                        Some(Disk::White) => self.config.white,
+                    };
+                art += &format!(" {} │", symbol);
+            }
+            art += "\n";
+        }
+        art += &border("└", "┴", "┘");
+        art += &format!(
+            "Black={}, White={}\n{}\n",
+            board.count(Disk::Black),
+            board.count(Disk::White),
+            self.engine.status_text()
+        );
+
+        art
+    }
+
+    /// Plays whole games between two computer players without showing
+    /// the moves, then sums up how black fared. Random players are seeded
+    /// from the given seed, so that the same command gives the same games.
+    fn selfplay(
+        &mut self,
+        words: &[&str],
+        out: &mut dyn Write,
+    ) -> io::Result<()> {
+        let verbose = words.contains(&"--verbose");
+        let words: Vec<&str> = words
+            .iter()
+            .copied()
+            .filter(|&word| word != "--verbose")
+            .collect();
+        let games = words.first().and_then(|n| n.parse::<u32>().ok());
+        let names = (
+            words.get(1).copied().unwrap_or("random"),
+            words.get(2).copied().unwrap_or("random"),
+        );
+        let seed = match words.get(3) {
+            Some(seed) => seed.parse::<u64>().ok(),
+            None => Some(self.rng.gen()),
+        };
+        let players = seed.and_then(|seed| {
+            let black = selfplay_strategy(names.0, seed)?;
+            let white = selfplay_strategy(names.1, seed.wrapping_add(1))?;
+            Some((black, white))
+        });
+        let (games, (mut black, mut white)) = match (games, players) {
+            (Some(games), Some(players)) if games > 0 && words.len() <= 4 => {
+                (games, players)
+            }
+            _ => return writeln!(out, "Usage: {}", SELFPLAY_USAGE),
+        };
+
+        let start = Instant::now();
+        let (mut wins, mut draws, mut losses) = (0, 0, 0);
+        let (mut total_lead, mut total_plies) = (0, 0);
+        for number in 1..=games {
+            let mut game = Game::new();
+            game.config = self.config;
+            game.engine.action(Command::Init);
+            let status = game.engine.simulate_to_end(&mut *black, &mut *white);
+            let plies = game.engine.move_history().len();
+            let (b, w) = match status {
+                GameStatus::GameOver(b, w) => (b, w),
+                _ => {
+                    let board = game.engine.current_board();
+                    (
+                        board.count(Disk::Black) as u8,
+                        board.count(Disk::White) as u8,
+                    )
+                }
+            };
+            let result = match b.cmp(&w) {
+                Ordering::Greater => {
+                    wins += 1;
+                    format!("Black wins {}–{}", b, w)
+                }
+                Ordering::Less => {
+                    losses += 1;
+                    format!("White wins {}–{}", w, b)
+                }
+                Ordering::Equal => {
+                    draws += 1;
+                    format!("draw {}–{}", b, w)
+                }
+            };
+            total_lead += i32::from(b) - i32::from(w);
+            total_plies += plies;
+            writeln!(out, "Game {}: {} in {} plies", number, result, plies)?;
+            if verbose {
+                game.render(out)?;
+            }
+        }
+
+        let games_f = f64::from(games);
+        writeln!(
+            out,
+            "Black ({}) against white ({}): {} wins, {} draws, {} losses",
+            names.0, names.1, wins, draws, losses
+        )?;
+        writeln!(
+            out,
+            "Average lead for black: {:+.2}, average length: {:.2} plies",
+            f64::from(total_lead) / games_f,
+            total_plies as f64 / games_f
+        )?;
+        writeln!(out, "Time: {:.2}s", start.elapsed().as_secs_f64())
+    }
+
+    /// Prints the disk counts and a sparkline of black's lead after each
+    /// ply, adding the result once the game is over.
+    fn print_score(&self, out: &mut dyn Write) -> io::Result<()> {
+        let board = self.engine.current_board();
+        let black = board.count(Disk::Black);
+        let white = board.count(Disk::White);
+        let lead = black as i32 - white as i32;
+        writeln!(out, "Black={}, White={}, lead {:+}", black, white, lead)?;
+
+        let leads: Vec<i32> = self
+            .engine
+            .history_boards()
+            .iter()
+            .skip(1)
+            .map(|(_, board)| {
+                board.count(Disk::Black) as i32
+                    - board.count(Disk::White) as i32
+            })
+            .collect();
+        if leads.is_empty() {
+            writeln!(out, "Lead by ply: no moves yet")?;
+        } else {
+            writeln!(out, "Lead by ply: {}", sparkline(&leads))?;
+        }
+        if self.engine.status().is_over() {
+            writeln!(out, "{}", self.engine.status_text())?;
+        }
+
+        Ok(())
+    }
+
+    fn print_hint(&self, out: &mut dyn Write) -> io::Result<()> {
+        if self.engine.status().is_over() {
+            return writeln!(out, "The game is over.");
+        }
+
+        let moves = self.engine.legal_moves();
+        match self.engine.hint() {
+            Some(hint) => {
+                let moves: Vec<String> =
+                    moves.iter().map(|coord| coord.to_string()).collect();
+                writeln!(
+                    out,
+                    "Legal: {} — suggestion: {}",
+                    moves.join(" "),
+                    hint
+                )
+            }
+            None => writeln!(out, "No legal moves, so you must pass."),
+        }
+    }
+
+    /// Counts the positions reached from the current one at each depth
+    /// up to `depth`, with the time each count took.
+    fn print_perft(&self, depth: u8, out: &mut dyn Write) -> io::Result<()> {
+        for depth in 1..=depth {
+            let start = Instant::now();
+            let nodes = self.engine.perft_from_current(depth);
+            let time = format_seconds(start.elapsed());
+            writeln!(out, "perft {}: {} ({})", depth, nodes, time)?;
+        }
+
+        Ok(())
+    }
+
+    /// Lists the legal moves with their scores for the side to move and
+    /// the line expected after each, best first, searching at most
+    /// `MAX_EVAL_DEPTH` plies.
+    fn print_eval(&self, depth: u8, out: &mut dyn Write) -> io::Result<()> {
+        if self.engine.status().is_over() {
+            return writeln!(out, "The game is over.");
+        }
+        if self.engine.legal_moves().is_empty() {
+            return writeln!(out, "No legal moves, so you must pass.");
+        }
+        if depth > MAX_EVAL_DEPTH {
+            writeln!(out, "Searching {} plies at most.", MAX_EVAL_DEPTH)?;
+        }
+
+        let depth = depth.min(MAX_EVAL_DEPTH);
+        let board = self.engine.current_board();
+        let disk = self.engine.turn().to_disk();
+        for (coord, score) in self.engine.candidate_moves(depth) {
+            let line: Vec<String> =
+                search::principal_variation(board, disk, coord, depth)
+                    .iter()
+                    .map(|coord| match coord {
+                        Some(coord) => coord.to_string(),
+                        None => "--".to_string(),
+                    })
+                    .collect();
+            writeln!(out, "{} {:>+3}  ({})", coord, score, line.join(" "))?;
+        }
+
+        Ok(())
+    }
+}
+
+// =====================================================================
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "line-editing")]
+    use super::CommandCompleter;
+    use super::LineReader;
+    use super::ScriptError;
+    use super::{command_loop, format_history, json_string, sparkline};
+    use super::{edit_distance, expand_alias, suggest_command};
+    use super::{parse_coordinate, CoordinateInputError};
+    use super::{Game, RenderConfig, WATCH_DEPTH};
+    use crate::ansi;
+    use crate::board::Board;
+    use crate::book::OpeningBook;
+    use crate::engine::{Command, Engine, GameStatus, Side};
+    use crate::position::Coordinate;
+    use crate::strategy::AiStrategy;
+    use crate::timing::{Clock, MoveTimer};
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+    use std::cell::{Cell, RefCell};
+    use std::fs;
+    use std::path::Path;
+    use std::rc::Rc;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn run_script(game: &mut Game, script: &str) -> String {
+        let mut out = Vec::new();
+        game.run_script(&mut script.as_bytes(), &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn parse_coordinate_errors() {
+        assert_eq!(parse_coordinate(Some("c4")), Ok(Coordinate::new('c', 4)));
+        assert_eq!(
+            parse_coordinate(None),
+            Err(CoordinateInputError::MissingInput)
+        );
+        assert_eq!(
+            parse_coordinate(Some("c")),
+            Err(CoordinateInputError::TooShort("c".to_string()))
+        );
+        assert_eq!(
+            parse_coordinate(Some("44")),
+            Err(CoordinateInputError::InvalidColumn('4'))
+        );
+        assert_eq!(
+            parse_coordinate(Some("cx")),
+            Err(CoordinateInputError::InvalidRow("x".to_string()))
+        );
+        assert_eq!(
+            parse_coordinate(Some("i4")),
+            Err(CoordinateInputError::OutOfRange('i', 4))
+        );
+        assert_eq!(
+            parse_coordinate(Some("c9")),
+            Err(CoordinateInputError::OutOfRange('c', 9))
+        );
+    }
+
+    #[test]
+    fn render_config_parse() {
+        assert_eq!(RenderConfig::parse("xo."), Some(RenderConfig::default()));
+        let config = RenderConfig {
+            black: '#',
+            white: 'O',
+            empty: '-',
+            ..RenderConfig::default()
+        };
+        assert_eq!(RenderConfig::parse("# O -"), Some(config));
+        assert_eq!(RenderConfig::parse("#O"), None);
+        assert_eq!(RenderConfig::parse("#O-+"), None);
+        assert_eq!(RenderConfig::parse("##-"), None);
+        assert_eq!(RenderConfig::parse("#O#"), None);
+        assert_eq!(RenderConfig::parse("# - -"), None);
+    }
+
+    #[test]
+    fn grid_with_color() {
+        let mut game = Game::new();
+        game.engine.action(Command::Init);
+        game.engine.action(Command::Move(Coordinate::new('f', 5)));
+        game.config.color = true;
+        let f5 = Some(Coordinate::new('f', 5));
+        let board = game.engine.current_board();
+        assert_eq!(game.grid(), ansi::render_grid(board, f5));
+
+        run_script(&mut game, "symbols # O -\n");
+        assert!(game.config.color);
+    }
+
+    #[test]
+    fn grid_with_custom_symbols() {
+        let mut game = Game::new();
+        game.engine.action(Command::Init);
+        game.config = RenderConfig::parse("#O-").unwrap();
+
+        let expected = "   a  b  c  d  e  f  g  h
+1  -  -  -  -  -  -  -  - \n\
+2  -  -  -  -  -  -  -  - \n\
+3  -  -  -  -  -  -  -  - \n\
+4  -  -  -  O  #  -  -  - \n\
+5  -  -  -  #  O  -  -  - \n\
+6  -  -  -  -  -  -  -  - \n\
+7  -  -  -  -  -  -  -  - \n\
+8  -  -  -  -  -  -  -  - \n";
+        assert_eq!(game.grid(), expected);
+    }
+
+    #[test]
+    fn grid_with_hints() {
+        let mut game = Game::new();
+        game.engine.action(Command::Init);
+        run_script(&mut game, "hints on\n");
+
+        let expected = "   a  b  c  d  e  f  g  h
+1  .  .  .  .  .  .  .  . \n\
+2  .  .  .  .  .  .  .  . \n\
+3  .  .  .  *  .  .  .  . \n\
+4  .  .  *  o  x  .  .  . \n\
+5  .  .  .  x  o  *  .  . \n\
+6  .  .  .  .  *  .  .  . \n\
+7  .  .  .  .  .  .  .  . \n\
+8  .  .  .  .  .  .  .  . \n";
+        assert_eq!(game.grid(), expected);
+        run_script(&mut game, "symbols # O -\n");
+        assert_eq!(game.grid().matches('*').count(), 4);
+
+        let board: Board = "\
+xxxxxxxo xxxxxxxx xxxxxxxx xxxxxxxx oooooooo oooooooo oooooooo oooooooo"
+            .parse()
+            .unwrap();
+        game.engine = Engine::from_board(board, Side::Dark);
+        game.engine.action(Command::Init);
+        assert!(!game.grid().contains('*'));
+
+        let output = run_script(&mut game, "hints off\nhints maybe\n");
+        assert!(output.ends_with("Usage: hints on|off\n"));
+        assert!(!game.config.hints);
+    }
+
+    #[test]
+    fn render_with_two_symbol_sets() {
+        let mut game = Game::new();
+        game.engine.action(Command::Init);
+        run_script(&mut game, "move f5\n");
+
+        let default = run_script(&mut game, "show\n");
+        let custom = run_script(&mut game, "symbols B W _\nshow\n");
+        assert!(default.contains("5  .  .  .  x  x [x] .  . \n"));
+        assert!(default.contains("\nBlack=4, White=1\n"));
+        assert!(custom.contains("5  _  _  _  B  B [B] _  _ \n"));
+        assert!(custom.contains("\nBlack (B)=4, White (W)=1\n"));
+        assert_eq!(custom.lines().count(), default.lines().count() * 2);
+
+        let output = run_script(&mut game, "symbols B B _\n");
+        assert_eq!(output, "Invalid symbols\n");
+    }
+
+    #[cfg(feature = "line-editing")]
+    #[test]
+    fn complete_commands_and_moves() {
+        let completer = CommandCompleter::new();
+        let mut game = Game::new();
+        game.engine.action(Command::Init);
+        *completer.legal_moves.borrow_mut() = game.engine.legal_moves();
+
+        let (start, candidates) = completer.candidates("h", 1);
+        assert_eq!(start, 0);
+        assert_eq!(candidates, vec!["help", "hint", "hints", "history"]);
+        let (start, candidates) = completer.candidates("  qu", 4);
+        assert_eq!(start, 2);
+        assert_eq!(candidates, vec!["quit"]);
+
+        let (start, candidates) = completer.candidates("move ", 5);
+        assert_eq!(start, 5);
+        assert_eq!(candidates, vec!["d3", "c4", "f5", "e6"]);
+        let (start, candidates) = completer.candidates("move f", 6);
+        assert_eq!(start, 5);
+        assert_eq!(candidates, vec!["f5"]);
+        assert_eq!(completer.candidates("move f5 ", 8).1.len(), 0);
+        assert_eq!(completer.candidates("undo ", 5).1.len(), 0);
+    }
+
+    #[test]
+    fn report_flips_and_undo() {
+        let mut game = Game::new();
+        game.engine.action(Command::Init);
+        let output = run_script(&mut game, "move f5\n");
+        assert!(output.starts_with("Black played f5, flipping 1 disk (e5)\n"));
+        let output = run_script(&mut game, "undo\nundo\n");
+        assert!(output.starts_with("Took back Black's f5.\n"));
+        assert!(!output.contains("Took back White"));
+
+        let board: Board = "\
+........ .x.x.x.. ..ooo... .xo.ox.. ..ooo... .x.x.x.. ........ ........"
+            .parse()
+            .unwrap();
+        game.engine = Engine::from_board(board, Side::Dark);
+        game.engine.action(Command::Init);
+        let output = run_script(&mut game, "move d4\n");
+        let line =
+            "Black played d4, flipping 8 disks (c3, d3, e3, c4, e4, c5, …)";
+        assert_eq!(output.lines().next(), Some(line));
+    }
+
+    #[test]
+    fn redo_command() {
+        let mut game = Game::new();
+        game.engine.action(Command::Init);
+        run_script(&mut game, "move f5\nmove d6\nundo\nundo\n");
+        assert!(game.engine.move_history().is_empty());
+
+        let output = run_script(&mut game, "redo\n");
+        assert!(output.ends_with("Move 1: Redo, and White's turn.\n"));
+        assert_eq!(game.engine.move_history().len(), 1);
+
+        let output = run_script(&mut game, "redo 3\n");
+        assert!(output.ends_with("Move 2: Redo, and Black's turn.\n"));
+        assert_eq!(game.engine.move_history().len(), 2);
+
+        let output = run_script(&mut game, "redo\nredo x\n");
+        assert_eq!(output, "Nothing to redo\nInvalid count: x\n");
+        assert_eq!(game.engine.move_history().len(), 2);
+    }
+
+    #[test]
+    fn vs_command() {
+        let mut game = Game::new();
+        game.engine.action(Command::Init);
+        let output = run_script(&mut game, "vs human robot\n");
+        assert_eq!(output, "Usage: vs {black} {white}\n");
+
+        run_script(&mut game, "vs human ai:1\nmove f5\n");
+        assert_eq!(game.engine.move_history().len(), 2);
+        assert_eq!(game.engine.turn(), Side::Dark);
+
+        run_script(&mut game, "vs random random\n");
+        assert!(game.engine.legal_moves().is_empty());
+        match game.engine.status() {
+            GameStatus::GameOver(_, _) => (),
+            status => panic!("game not over: {:?}", status),
+        }
+    }
+
+    #[test]
+    fn save_and_load_commands() {
+        let dir = std::env::temp_dir()
+            .join(format!("othello-cui-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("game.txt");
+        let path = path.to_str().unwrap();
+
+        let mut game = Game::new();
+        game.engine.action(Command::Init);
+        let script = "\
+move f5\nmove d6\nmove c3\nmove d3\nmove c4\nmove f4\n";
+        run_script(&mut game, script);
+        let output = run_script(&mut game, &format!("save {}\n", path));
+        assert_eq!(output, format!("Saved 6 moves to {}\n", path));
+
+        let mut loaded = Game::new();
+        loaded.engine.action(Command::Init);
+        let output = run_script(&mut loaded, &format!("load {}\n", path));
+        assert!(output.ends_with("Loaded 6 moves. Black's turn.\n"));
+        assert_eq!(loaded.engine.transcript(), "f5d6c3d3c4f4");
+        assert_eq!(loaded.grid(), game.grid());
+
+        let missing = dir.join("missing.txt");
+        let missing = missing.to_str().unwrap();
+        let output = run_script(&mut loaded, &format!("load {}\n", missing));
+        assert!(output.starts_with(&format!("Failed to load {}: ", missing)));
+
+        std::fs::write(path, "f5d6a1\n").unwrap();
+        let output = run_script(&mut loaded, &format!("load {}\n", path));
+        let expected =
+            format!("Failed to load {}: Illegal move a1 at step 3\n", path);
+        assert_eq!(output, expected);
+        assert_eq!(loaded.engine.transcript(), "f5d6c3d3c4f4");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn export_command() {
+        let mut game = Game::new();
+        game.engine.action(Command::Init);
+        let output = run_script(&mut game, "export\n");
+        let codec = "\
+...........................ox......xo...........................";
+        assert!(output.starts_with("   a  b  c  d  e  f  g  h\n"));
+        assert!(output.contains(&format!("Position: {}\n", codec)));
+        assert!(output.ends_with("Black to move\n"));
+
+        run_script(&mut game, "move f5\n");
+        let output = run_script(&mut game, "export\n");
+        assert!(output.ends_with("White to move\n"));
+    }
+
+    #[test]
+    fn export_ascii_art() {
+        let mut game = Game::new();
+        game.engine.action(Command::Init);
+        let art = game.export_ascii_art();
+        let lines: Vec<&str> = art.lines().collect();
+        assert_eq!(lines.len(), 20);
+        assert_eq!(lines[0], "    a   b   c   d   e   f   g   h");
+        assert_eq!(lines[1], "  ┌───┬───┬───┬───┬───┬───┬───┬───┐");
+        assert_eq!(lines[2], "1 │   │   │   │   │   │   │   │   │");
+        assert_eq!(lines[3], "  ├───┼───┼───┼───┼───┼───┼───┼───┤");
+        assert_eq!(lines[8], "4 │   │   │   │ o │ x │   │   │   │");
+        assert_eq!(lines[17], "  └───┴───┴───┴───┴───┴───┴───┴───┘");
+        assert_eq!(lines[18], "Black=2, White=2");
+        assert_eq!(lines[19], "Black's turn.");
+        assert_eq!(art.matches('│').count(), 9 * 8);
+        assert!(lines[1..18].iter().all(|line| line.chars().count() == 35));
+
+        let output = run_script(&mut game, "export art\n");
+        assert_eq!(output, art);
+    }
+
+    #[test]
+    fn score_command() {
+        assert_eq!(sparkline(&[-3, 0, 4, 4]), "▁▄██");
+        assert_eq!(sparkline(&[2, 2]), "▄▄");
+
+        let mut game = Game::new();
+        game.engine.action(Command::Init);
+        let output = run_script(&mut game, "score\n");
+        assert_eq!(
+            output,
+            "Black=2, White=2, lead +0\nLead by ply: no moves yet\n"
+        );
+
+        let script = "\
+move f5\nmove d6\nmove c3\nmove d3\nmove c4\nmove f4\nmove f6\nmove f3\n";
+        run_script(&mut game, script);
+        assert_eq!(game.engine.move_history().len(), 8);
+        let output = run_script(&mut game, "score\n");
+        let expected = "\
+Black=6, White=6, lead +0\nLead by ply: █▁█▁█▁█▁\n";
+        assert_eq!(output, expected);
+
+        let board: Board = "\
+xxxxxxxo xxxxxxxx xxxxxxxx xxxxxxxx oooooooo oooooooo oooooooo oooooooo"
+            .parse()
+            .unwrap();
+        game.engine = Engine::from_board(board, Side::Dark);
+        game.engine.action(Command::Init);
+        let output = run_script(&mut game, "score\n");
+        let expected = "Black=31, White=33, lead -2\n\
+Lead by ply: no moves yet\nGame over: White wins 33–31.\n";
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn selfplay_command() {
+        let mut game = Game::new();
+        let output = run_script(&mut game, "selfplay 3 random random 7\n");
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 6);
+        let expected = "\
+Game 1: White wins 44–20 in 60 plies
+Game 2: White wins 38–26 in 60 plies
+Game 3: White wins 33–31 in 60 plies
+Black (random) against white (random): 0 wins, 0 draws, 3 losses
+Average lead for black: -12.67, average length: 60.00 plies";
+        assert_eq!(lines[..5].join("\n"), expected);
+        assert!(lines[5].starts_with("Time: "));
+        let again = run_script(&mut game, "selfplay 3 random random 7\n");
+        assert_eq!(again.lines().take(5).collect::<Vec<_>>(), lines[..5]);
+
+        let output =
+            run_script(&mut game, "selfplay 1 greedy ai:1 --verbose\n");
+        assert!(output.contains("\nBlack=") && output.contains("Game over"));
+        let output = run_script(&mut game, "selfplay 0\nselfplay 2 human\n");
+        let usage =
+            "Usage: selfplay {games} [black] [white] [seed] [--verbose]\n";
+        assert_eq!(output, usage.repeat(2));
+    }
+
+    #[test]
+    fn ai_command() {
+        let mut game = Game::new();
+        game.engine.action(Command::Init);
+        let output = run_script(&mut game, "ai white chess\n");
+        assert!(output.starts_with("Usage: ai"));
+
+        let output = run_script(&mut game, "ai random 3\n");
+        assert_eq!(output, "White is played by random 3.\n");
+        let script = "move f5\nmove e6\nmove c5\n";
+        let output = run_script(&mut game, script);
+        assert!(output.contains("White played f6, flipping 1 disk (e5)\n"));
+        assert_eq!(game.engine.transcript(), "f5f6e6d6c5e3");
+
+        let mut replay = Game::new();
+        replay.engine.action(Command::Init);
+        run_script(&mut replay, "ai random 3\n");
+        run_script(&mut replay, script);
+        assert_eq!(replay.engine.transcript(), "f5f6e6d6c5e3");
+
+        run_script(&mut game, "undo\n");
+        assert_eq!(game.engine.transcript(), "f5f6e6d6");
+        assert_eq!(game.engine.turn(), Side::Dark);
+
+        run_script(&mut game, "ai off\nai black greedy\n");
+        assert_eq!(game.engine.move_history().len(), 5);
+        assert_eq!(game.engine.turn(), Side::Light);
+    }
+
+    #[test]
+    fn command_loop_stops_at_end_of_input() {
+        let mut game = Game::new();
+        game.engine.action(Command::Init);
+        let input = "move f5\nmove d6\n".as_bytes();
+        let mut reader = LineReader::Plain(Box::new(input));
+        let legal_moves = RefCell::new(Vec::new());
+        let mut out = Vec::new();
+
+        let result =
+            command_loop(&mut game, &mut reader, &legal_moves, &mut out);
+        assert_eq!(result, Ok(()));
+        assert_eq!(game.engine.transcript(), "f5d6");
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.ends_with("Command? "));
+    }
+
+    #[test]
+    fn hint_command() {
+        let mut game = Game::new();
+        game.engine.action(Command::Init);
+        let output = run_script(&mut game, "hint\n");
+        assert_eq!(output, "Legal: d3 c4 f5 e6 — suggestion: d3\n");
+        assert!(game.engine.move_history().is_empty());
+
+        let board: Board = "\
+ox...... ........ ........ ........ ........ ........ ........ ........"
+            .parse()
+            .unwrap();
+        game.engine = Engine::from_board(board, Side::Dark);
+        game.engine.action(Command::Init);
+        let output = run_script(&mut game, "hint\nquit\nhint\n");
+        assert_eq!(output, "No legal moves, so you must pass.\n");
+
+        game.engine.action(Command::Pass);
+        game.engine.action(Command::Move(Coordinate::new('c', 1)));
+        let output = run_script(&mut game, "hint\n");
+        assert_eq!(output, "The game is over.\n");
+    }
+
+    #[test]
+    fn render_only_after_changes() {
+        let renders = |out: &str| out.matches("Black=").count();
+        let mut game = Game::new();
+        game.engine.action(Command::Init);
+
+        assert_eq!(renders(&run_script(&mut game, "help\n")), 0);
+        assert_eq!(renders(&run_script(&mut game, "move z9\nredo x\n")), 0);
+        let out = run_script(&mut game, "move a1\n");
+        assert_eq!(renders(&out), 0);
+        assert!(out.contains("a1 would not flip any disks."));
+        assert_eq!(renders(&run_script(&mut game, "show\nboard\n")), 2);
+        assert_eq!(renders(&run_script(&mut game, "move f5\nundo\n")), 2);
+    }
+
+    #[test]
+    fn watch_plays_to_the_end() {
+        let mut game = Game::new();
+        game.black = Box::new(AiStrategy(WATCH_DEPTH));
+        game.white = Box::new(AiStrategy(WATCH_DEPTH));
+        game.engine.action(Command::Init);
+
+        let mut out = Vec::new();
+        game.play_computer_moves(&mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(matches!(game.engine.status(), GameStatus::GameOver(_, _)));
+        assert!(out.trim_end().ends_with(&game.engine.status().to_string()));
+    }
+
+    #[test]
+    fn history_command() {
+        let c1 = Coordinate::new('c', 1);
+        let h8 = Coordinate::new('h', 8);
+        let moves = [Some(c1), None, Some(h8)];
+        assert_eq!(format_history(&moves, 3), "1. c1 --  2. h8\n");
+        assert_eq!(format_history(&moves, 1), "1. c1 | --  2. h8\n");
+        assert_eq!(format_history(&[], 0), "");
+
+        let long = format_history(&[Some(c1); 60], 60);
+        let lines: Vec<&str> = long.lines().collect();
+        assert!(lines.len() > 1);
+        assert!(lines.iter().all(|line| line.len() <= 80));
+        assert!(long.ends_with("30. c1 c1\n"));
+
+        let mut game = Game::new();
+        game.engine.action(Command::Init);
+        let out = run_script(&mut game, "move f5\nmove d6\nmove c3\nundo\n");
+        assert!(out.ends_with("Black's turn.\n"));
+        let out = run_script(&mut game, "history\nmoves\n");
+        assert_eq!(out, "1. f5 d6  2. | c3\n".repeat(2));
+    }
+
+    #[test]
+    fn run_script_files() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/scripts");
+        let output = super::run_script(&dir.join("opening.txt")).unwrap();
+        let expected = fs::read_to_string(dir.join("opening.out")).unwrap();
+        assert_eq!(output, expected);
+        let output = super::run_script(&dir.join("undo.txt")).unwrap();
+        let expected = fs::read_to_string(dir.join("undo.out")).unwrap();
+        assert_eq!(output, expected);
+        let output = super::run_script(&dir.join("undo_ai.txt")).unwrap();
+        let expected = fs::read_to_string(dir.join("undo_ai.out")).unwrap();
+        assert_eq!(output, expected);
+        let output = super::run_script(&dir.join("redo_ai.txt")).unwrap();
+        let expected = fs::read_to_string(dir.join("redo_ai.out")).unwrap();
+        assert_eq!(output, expected);
+
+        match super::run_script(&dir.join("unknown.txt")) {
+            Err(ScriptError::UnknownCommand(3, command)) => {
+                assert_eq!(command, "pass")
+            }
+            result => panic!("unexpected result: {:?}", result),
+        }
+        let missing = dir.join("missing.txt");
+        assert!(matches!(
+            super::run_script(&missing),
+            Err(ScriptError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn init_lets_the_computer_open() {
+        let mut game = Game::new();
+        game.black = Box::new(AiStrategy(1));
+        run_script(&mut game, "init\n");
+        assert_eq!(game.engine.move_history().len(), 1);
+        assert!(game.is_human_turn());
+
+        run_script(&mut game, "move d6\ninit!\n");
+        assert_eq!(game.engine.move_history().len(), 1);
+        assert!(game.is_human_turn());
+    }
+
+    #[test]
+    fn move_refused_on_the_computers_turn() {
+        let mut game = Game::new();
+        game.engine.action(Command::Init);
+        run_script(&mut game, "move f5\n");
+        game.white = Box::new(AiStrategy(1));
+
+        let output = run_script(&mut game, "move f6\n");
+        assert_eq!(output, "White is played by the computer.\n");
+        assert_eq!(game.engine.move_history().len(), 1);
+    }
+
+    #[test]
+    fn ai_and_vs_play_from_the_book() {
+        let book = OpeningBook::load("f5d6c3").unwrap();
+        let mut game = Game::new();
+        game.book = Some(Arc::new(book));
+        game.engine.action(Command::Init);
+
+        // Without the book, minimax 1 opens with d3.
+        run_script(&mut game, "ai black minimax 1\n");
+        assert_eq!(game.engine.transcript(), "f5");
+
+        run_script(&mut game, "init!\nvs ai:1 ai:1\n");
+        assert!(game.engine.transcript().starts_with("f5d6c3"));
+    }
+
+    #[test]
+    fn lang_command() {
+        let mut game = Game::new();
+        game.engine.action(Command::Init);
+        let output = run_script(&mut game, "lang ja\n");
+        assert!(output.ends_with("Move 0: ゲーム開始！黒の番です。\n"));
+        let output = run_script(&mut game, "undo\n");
+        assert!(output.ends_with("Move 0: 戻せません！黒の番です。\n"));
+        let output = run_script(&mut game, "lang fr\n");
+        assert_eq!(output, "Usage: lang en|ja\n");
+        let output = run_script(&mut game, "lang en\n");
+        assert!(output.ends_with("Move 0: Can't undo! Black's turn.\n"));
+    }
+
+    #[test]
+    fn name_command() {
+        let mut game = Game::new();
+        game.engine.action(Command::Init);
+        assert_eq!(game.prompt(), "Command? ");
+
+        let output = run_script(&mut game, "name black Alice\n");
+        assert!(output.ends_with("Move 0: Alice's turn.\n"));
+        assert_eq!(game.prompt(), "Alice (black) — Command? ");
+        let output = run_script(&mut game, "move f5\nname white Bob Jr\n");
+        assert!(output.ends_with("Move 1: Bob Jr's turn.\n"));
+        assert_eq!(game.prompt(), "Bob Jr (white) — Command? ");
+        let output = run_script(&mut game, "name white\nname red Carol\n");
+        assert_eq!(output, "Usage: name black|white {name}\n".repeat(2));
+
+        let board: Board = "\
+xxxxxxxo xxxxxxxx xxxxxxxx xxxxxxxx oooooooo oooooooo oooooooo oooooooo"
+            .parse()
+            .unwrap();
+        game.engine = Engine::from_board(board, Side::Dark);
+        game.engine.action(Command::Init);
+        let output = run_script(&mut game, "name white Bob\n");
+        assert!(output.ends_with("Game over: Bob wins 33–31.\n"));
+        let output = run_script(&mut game, "name black Alice\n");
+        assert!(output.ends_with("Game over: Bob wins 33–31.\n"));
+
+        let dir = std::env::temp_dir()
+            .join(format!("othello-name-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("game.txt");
+        let path = path.to_str().unwrap();
+        let mut game = Game::new();
+        game.engine.action(Command::Init);
+        run_script(&mut game, "name white Bob\nmove f5\n");
+        run_script(&mut game, &format!("save {}\n", path));
+        let saved = std::fs::read_to_string(path).unwrap();
+        assert_eq!(saved, "name white Bob\nf5\n");
+
+        let mut loaded = Game::new();
+        loaded.engine.action(Command::Init);
+        let output = run_script(&mut loaded, &format!("load {}\n", path));
+        assert!(output.ends_with("Loaded 1 moves. Bob's turn.\n"));
+        assert_eq!(loaded.engine.name(Side::Light), "Bob");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn confirm_quit_and_init() {
+        let mut game = Game::new();
+        game.engine.action(Command::Init);
+        let output = run_script(&mut game, "init\n");
+        assert!(output.ends_with("Move 0: Game start! Black's turn.\n"));
+
+        let output = run_script(&mut game, "move f5\ninit\nn\n");
+        assert!(
+            output.ends_with("Discard the current game? (y/N)\nCancelled.\n")
+        );
+        assert_eq!(game.engine.transcript(), "f5");
+        let output = run_script(&mut game, "init\nmove d6\n");
+        assert!(output.ends_with("Cancelled.\n"));
+        assert_eq!(game.engine.transcript(), "f5");
+        let output = run_script(&mut game, "init\ny\n");
+        assert!(output.ends_with("Move 0: Game start! Black's turn.\n"));
+        assert_eq!(game.engine.transcript(), "");
+
+        run_script(&mut game, "move f5\ninit!\n");
+        assert_eq!(game.engine.transcript(), "");
+
+        let mut out = Vec::new();
+        run_script(&mut game, "move f5\n");
+        assert!(game.execute("quit", &mut out).unwrap());
+        assert!(game.execute("", &mut out).unwrap());
+        assert!(game.execute("quit", &mut out).unwrap());
+        assert!(!game.execute("YES", &mut out).unwrap());
+        assert!(!game.execute("quit!", &mut out).unwrap());
+    }
+
+    #[test]
+    fn json_output() {
+        assert_eq!(json_string("a\"b\\c\n\t"), "\"a\\\"b\\\\c\\n\\u0009\"");
+
+        let mut game = Game::new();
+        game.config.json = true;
+        game.engine.action(Command::Init);
+        let output = run_script(&mut game, "move f5\n");
+        assert_eq!(output.lines().count(), 1);
+        let line = output.lines().next().unwrap();
+        assert!(line.starts_with("{\"board\":\""));
+        assert!(line.contains(",\"to_move\":\"white\","));
+        assert!(line.contains(",\"legal_moves\":[\"f4\",\"d6\",\"f6\"],"));
+        assert!(line.contains(",\"black\":4,\"white\":1,"));
+        assert!(line.contains(",\"game_over\":false,"));
+        assert!(line.ends_with(",\"message\":\"White's turn.\"}"));
+    }
+
+    #[test]
+    fn seeded_random_players() {
+        let transcript = |seed| {
+            let mut game = Game::new();
+            game.rng = SmallRng::seed_from_u64(seed);
+            game.engine.action(Command::Init);
+            run_script(&mut game, "vs random random\n");
+            game.engine.transcript()
+        };
+
+        assert_eq!(transcript(5), transcript(5));
+        assert_ne!(transcript(5), transcript(6));
+    }
+
+    #[test]
+    fn times_command() {
+        struct FakeClock(Rc<Cell<Duration>>);
+
+        impl Clock for FakeClock {
+            fn now(&self) -> Duration {
+                self.0.get()
+            }
+        }
+
+        let now = Rc::new(Cell::new(Duration::from_secs(0)));
+        let mut game = Game::new();
+        game.timer = MoveTimer::new(Box::new(FakeClock(Rc::clone(&now))));
+        game.engine.action(Command::Init);
+        let output = run_script(&mut game, "times\n");
+        assert_eq!(output, "No moves timed yet.\n");
+
+        game.timer.start();
+        now.set(Duration::from_millis(4_200));
+        let output = run_script(&mut game, "move f5\n");
+        let status =
+            "Move 1: White's turn. (this move 4.2s, Black total 0:04)";
+        assert!(output.ends_with(&format!("{}\n", status)));
+
+        game.timer.start();
+        now.set(Duration::from_secs(70));
+        run_script(&mut game, "move d6\n");
+        game.timer.start();
+        now.set(Duration::from_secs(158));
+        let output = run_script(&mut game, "move c3\n");
+        let status =
+            "Move 3: White's turn. (this move 88.0s, Black total 1:32)";
+        assert!(output.ends_with(&format!("{}\n", status)));
+
+        let output = run_script(&mut game, "times\n");
+        assert_eq!(
+            output,
+            "  1. Black f5 4.2s\n  2. White d6 65.8s\n  3. Black c3 88.0s\n\
+             Black total 1:32, White total 1:05\n"
+        );
+
+        let output = run_script(&mut game, "undo\nshow\n");
+        let status =
+            "Undo, and Black's turn. (this move 65.8s, White total 1:05)";
+        assert!(output.ends_with(&format!("Move 2: {}\n", status)));
+        let output = run_script(&mut game, "init!\ntimes\n");
+        assert!(output.ends_with("No moves timed yet.\n"));
+    }
+
+    /// Runs `script` a line at a time, checking the board drawn after
+    /// each against `tests/golden/{name}.txt`. Set `UPDATE_GOLDEN` to
+    /// write the file instead, after a deliberate change to the output.
+    fn check_golden(name: &str, game: &mut Game, script: &[&str]) {
+        let mut text = format!("== start\n{}", game.render_to_string());
+        for line in script {
+            run_script(game, &format!("{}\n", line));
+            text += &format!("== {}\n{}", line, game.render_to_string());
+        }
+
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/golden")
+            .join(format!("{}.txt", name));
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            fs::write(&path, &text).unwrap();
+            return;
+        }
+        let expected = fs::read_to_string(&path).unwrap();
+        assert!(
+            text == expected,
+            "{} differs from the output; rerun with UPDATE_GOLDEN=1 if \
+             the change is intended:\n{}",
+            path.display(),
+            text
+        );
+    }
+
+    #[test]
+    fn golden_opening() {
+        let mut game = Game::new();
+        game.engine.action(Command::Init);
+        let script = [
+            "move f5",
+            "move d6",
+            "move c3",
+            "move d3",
+            "move c4",
+            "undo",
+            "symbols # O _",
+            "hints on",
+            "move c4",
+        ];
+        check_golden("opening", &mut game, &script);
+    }
+
+    #[test]
+    fn golden_pass() {
+        let mut game = Game::new();
+        game.engine
+            .load_transcript(
+                "f5d6c6f6f7g7e6b6h7h8e7h6c7c8g6g5h4g4b8a8h5d8d3f3g8h3c5d7\
+                 b7c4c3b2f8a6f4g3b4e8b5e3a1a5g2g1e2f1h2h1f2a3c2d2b3b1a2d1a4",
+            )
+            .unwrap();
+        check_golden("pass", &mut game, &["move e1", "move c1", "move a7"]);
+    }
+
+    #[test]
+    fn rejected_move_messages() {
+        let mut game = Game::new();
+        game.engine.action(Command::Init);
+        let output = run_script(&mut game, "move d4\nmove c1\n");
+        assert_eq!(
+            output,
+            "d4 is already occupied. Black's turn.\n\
+             c1 would not flip any disks. Black's turn.\n"
+        );
+        assert!(game.engine.move_history().is_empty());
+
+        let output = run_script(&mut game, "lang ja\nmove e5\n");
+        assert!(output.ends_with("e5にはもう石があります。黒の番です。\n"));
+    }
+
+    #[test]
+    fn command_suggestions() {
+        assert_eq!(edit_distance("mvoe", "move"), 2);
+        assert_eq!(edit_distance("", "undo"), 4);
+        assert_eq!(edit_distance("redo", "redo"), 0);
+        assert_eq!(edit_distance("hnit", "hint"), 2);
+
+        assert_eq!(suggest_command("mvoe"), Some("move"));
+        assert_eq!(suggest_command("undoo"), Some("undo"));
+        assert_eq!(suggest_command("hlep"), Some("help"));
+        assert_eq!(suggest_command("x"), None);
+        assert_eq!(suggest_command("chess"), None);
+
+        assert_eq!(expand_alias("m"), "move");
+        assert_eq!(expand_alias("q!"), "quit!");
+        assert_eq!(expand_alias("mo"), "mo");
+    }
+
+    #[test]
+    fn command_aliases_and_typos() {
+        let mut game = Game::new();
+        game.engine.action(Command::Init);
+        run_script(&mut game, "m f5\nm d6\nu\n");
+        assert_eq!(game.engine.transcript(), "f5");
+        run_script(&mut game, "r\n");
+        assert_eq!(game.engine.transcript(), "f5d6");
+
+        let mut out = Vec::new();
+        game.execute("mvoe c3", &mut out).unwrap();
+        game.execute("frobnicate", &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "Unknown command 'mvoe' — did you mean 'move'?\n\
+             Unknown command: frobnicate\n"
+        );
+        assert_eq!(game.engine.transcript(), "f5d6");
+
+        let output = run_script(&mut game, "n!\n");
+        assert!(output.ends_with("Move 0: Game start! Black's turn.\n"));
+    }
+
+    #[test]
+    fn render_to_string_opening() {
+        let mut game = Game::new();
+        game.engine.action(Command::Init);
+        let text = game.render_to_string();
+        assert!(text.starts_with("   a  b  c  d  e  f  g  h\n"));
+        assert!(text.contains("\nBlack=2, White=2\n"));
+        assert!(text.ends_with("Move 0: Game start! Black's turn.\n"));
+
+        let mut out = Vec::new();
+        game.render(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), text);
+    }
+
+    #[test]
+    fn eval_command() {
+        let mut game = Game::new();
+        game.engine.action(Command::Init);
+        let output = run_script(&mut game, "eval 2\n");
+        let rows: Vec<&str> = output.lines().collect();
+        assert_eq!(rows.len(), 4);
+        let scores: Vec<i32> = rows
+            .iter()
+            .map(|row| row.split_whitespace().nth(1).unwrap().parse().unwrap())
+            .collect();
+        assert!(scores.windows(2).all(|pair| pair[0] >= pair[1]));
+        for row in &rows {
+            let coord = &row[..2];
+            assert!(row.contains(&format!("({} ", coord)), "{}", row);
+        }
+        assert!(game.engine.move_history().is_empty());
+
+        let output = run_script(&mut game, "eval 9\n");
+        assert!(output.starts_with("Searching 5 plies at most.\n"));
+        assert_eq!(output.lines().count(), 5);
+        let output = run_script(&mut game, "eval 0\n");
+        assert_eq!(output, "Usage: eval [depth]\n");
+
+        let board: Board = "\
+ox...... ........ ........ ........ ........ ........ ........ ........"
+            .parse()
+            .unwrap();
+        game.engine = Engine::from_board(board, Side::Dark);
+        game.engine.action(Command::Init);
+        let output = run_script(&mut game, "eval\n");
+        assert_eq!(output, "No legal moves, so you must pass.\n");
+    }
+
+    #[test]
+    fn resign_and_draw_commands() {
+        let mut game = Game::new();
+        game.engine.action(Command::Init);
+        let output = run_script(&mut game, "draw\n");
+        assert_eq!(output, "Draw offered. Black's turn.\n");
+        let output = run_script(&mut game, "move f5\ndraw\n");
+        assert!(output.contains("Game over: draw agreed."));
+        assert_eq!(run_script(&mut game, "draw\n"), "The game is over.\n");
+
+        let output = run_script(&mut game, "undo\nresign\n");
+        assert!(output.contains("Game over: White resigned, and Black wins."));
+        assert_eq!(game.engine.move_history().len(), 1);
+        assert_eq!(run_script(&mut game, "resign\n"), "The game is over.\n");
+        assert!(!game.in_progress());
+    }
+
+    #[test]
+    fn perft_command() {
+        let mut game = Game::new();
+        game.engine.action(Command::Init);
+        let output = run_script(&mut game, "perft 3\n");
+        let counts: Vec<&str> = output
+            .lines()
+            .map(|line| line.split(" (").next().unwrap())
+            .collect();
+        assert_eq!(counts, vec!["perft 1: 4", "perft 2: 12", "perft 3: 56"]);
+
+        let output = run_script(&mut game, "perft 10\n");
+        assert_eq!(output, "Depth 10 would take too long; try 9 or less.\n");
+        let output = run_script(&mut game, "perft\nperft 0\n");
+        assert_eq!(output, "Usage: perft {depth}\n".repeat(2));
+    }
+
+    #[test]
+    fn grid_last_move() {
+        let mut game = Game::new();
+        game.engine.action(Command::Init);
+        let start = "   a  b  c  d  e  f  g  h
+1  .  .  .  .  .  .  .  . 
+2  .  .  .  .  .  .  .  . 
+3  .  .  .  .  .  .  .  . 
+4  .  .  .  o  x  .  .  . 
+5  .  .  .  x  o  .  .  . 
+6  .  .  .  .  .  .  .  . 
+7  .  .  .  .  .  .  .  . 
+8  .  .  .  .  .  .  .  . 
+";
+        assert_eq!(game.grid(), start);
+
+        run_script(&mut game, "move f5\n");
+        assert_eq!(
+            game.grid(),
+            "   a  b  c  d  e  f  g  h
+1  .  .  .  .  .  .  .  . 
+2  .  .  .  .  .  .  .  . 
+3  .  .  .  .  .  .  .  . 
+4  .  .  .  o  x  .  .  . 
+5  .  .  .  x  x [x] .  . 
+6  .  .  .  .  .  .  .  . 
+7  .  .  .  .  .  .  .  . 
+8  .  .  .  .  .  .  .  . 
+"
+        );
+        assert!(!game.engine.current_board().to_string().contains('['));
+
+        run_script(&mut game, "undo\n");
+        assert_eq!(game.grid(), start);
     }
 }
@@ -1,14 +1,77 @@
-use crate::board::{Board, Disk};
+use crate::board::{Board, Disk, MoveErr};
+use crate::history::GameHistory;
+use crate::messages::{GameMessage, Lang};
 use crate::position::Coordinate;
+use crate::search;
+use crate::strategy::Strategy;
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::rc::{Rc, Weak};
 
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Command {
     Init,
     Quit,
     Undo,
+    Redo,
     Move(Coordinate),
+    Pass,
+    Resign(Side),
+    OfferDraw,
+    AcceptDraw,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GameStatus {
+    Continue(Side),
+    PassBack(Side),
+    GameOver(u8, u8),
+    /// The given side resigned, leaving the board as it was.
+    Resigned(Side),
+    DrawAgreed,
+}
+
+impl GameStatus {
+    /// Tells whether the game has ended, played out or not.
+    pub fn is_over(&self) -> bool {
+        !matches!(self, GameStatus::Continue(_) | GameStatus::PassBack(_))
+    }
+
+    /// Describes the status in English, calling the players by the given
+    /// names.
+    pub fn describe(&self, black: &str, white: &str) -> String {
+        Lang::English.status(*self, black, white)
+    }
+}
+
+impl fmt::Display for GameStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let black = Side::Dark.label();
+        let white = Side::Light.label();
+        write!(f, "{}", self.describe(black, white))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GameOverReason {
+    BoardFull,
+    NoMoves,
+    BlackResigned,
+    WhiteResigned,
+    DrawAgreed,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PlaceError {
+    IllegalMove(MoveErr),
+    GameOver,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PassError {
+    MovesAvailable,
+    GameOver,
 }
 
 // ---------------------------------------------------------------------
@@ -17,14 +80,35 @@ pub struct Engine {
     root: Rc<Node>,
     current: Rc<Node>,
     pub prompt: String,
+    pub auto_pass: bool,
+    names: [Option<String>; 2],
+    lang: Lang,
+    message: Option<GameMessage>,
+    history: Option<Rc<GameHistory>>,
+    redo_stack: Vec<Rc<Node>>,
+    /// Set when the game ended by a resignation or an agreed draw, which
+    /// undo takes back.
+    ending: Option<GameStatus>,
+    /// The side whose draw offer stands until the opponent moves.
+    draw_offer: Option<Side>,
+    /// Nodes reached by a move, by the Zobrist hash of their board and
+    /// side to move, so that transposed positions share one node.
+    transpositions: HashMap<u64, Weak<Node>>,
 }
 
 impl Engine {
     pub fn new() -> Engine {
+        Engine::with_turn(Side::Dark)
+    }
+
+    pub fn with_turn(turn: Side) -> Engine {
         let mut board = Board::new();
         board.init();
-        let turn = Side::Dark;
 
+        Engine::from_board(board, turn)
+    }
+
+    pub fn from_board(board: Board, turn: Side) -> Engine {
         let root = Rc::new(Node::new(board, turn));
         let current = Rc::clone(&root);
 
@@ -32,123 +116,616 @@ impl Engine {
             root,
             current,
             prompt: String::with_capacity(1024),
+            auto_pass: true,
+            names: [None, None],
+            lang: Lang::English,
+            message: None,
+            history: None,
+            redo_stack: Vec::new(),
+            ending: None,
+            draw_offer: None,
+            transpositions: HashMap::new(),
         }
     }
 
+    /// Returns a new engine along with a history that it keeps up to date
+    /// as moves are played and undone.
+    pub fn with_history() -> (Engine, Rc<GameHistory>) {
+        let history = Rc::new(GameHistory::new());
+        let mut engine = Engine::new();
+        engine.history = Some(Rc::clone(&history));
+
+        (engine, history)
+    }
+
     pub fn current_board(&self) -> &Board {
         &self.current.board
     }
 
+    pub fn turn(&self) -> Side {
+        self.current.turn
+    }
+
+    /// Returns the name shown for the player of `side`, by default
+    /// "Black" or "White" in the engine's language.
+    pub fn name(&self, side: Side) -> &str {
+        match &self.names[side as usize] {
+            Some(name) => name,
+            None => self.lang.side_name(side),
+        }
+    }
+
+    /// Tells whether the player of `side` was given a name of their own.
+    pub fn is_named(&self, side: Side) -> bool {
+        self.names[side as usize].is_some()
+    }
+
+    /// Renames the player of `side` and refreshes the prompt to match.
+    pub fn set_name(&mut self, side: Side, name: &str) {
+        self.names[side as usize] = Some(name.to_string());
+        self.update_status(None);
+    }
+
+    pub fn lang(&self) -> Lang {
+        self.lang
+    }
+
+    /// Switches the language of the prompt and refreshes it.
+    pub fn set_lang(&mut self, lang: Lang) {
+        self.lang = lang;
+        self.update_status(self.message);
+    }
+
+    /// Returns the message that came with the last command, if any.
+    pub fn message(&self) -> Option<GameMessage> {
+        self.message
+    }
+
+    /// Describes the status like `status().to_string()`, but with the
+    /// players' names and in the engine's language.
+    pub fn status_text(&self) -> String {
+        let black = self.name(Side::Dark);
+        let white = self.name(Side::Light);
+        self.lang.status(self.status(), black, white)
+    }
+
+    /// Returns every square of the current board in row-major order,
+    /// from a1, b1, ... to h8.
+    pub fn snapshot(&self) -> [Option<Disk>; 64] {
+        let board = &self.current.board;
+        let mut cells = [None; 64];
+        for coord in Coordinate::iter_all() {
+            cells[coord.to_index()] = board.get_disk(coord);
+        }
+
+        cells
+    }
+
+    pub fn move_history(&self) -> Vec<Option<Coordinate>> {
+        let mut history = Vec::new();
+        let mut node = Rc::clone(&self.current);
+        while let Some(parent) = node.get_parent() {
+            history.push(parent.key_of(&node).unwrap());
+            node = parent;
+        }
+        history.reverse();
+
+        history
+    }
+
+    /// Returns the moves that have been undone and can be redone, in the
+    /// order they were first played.
+    pub fn redo_moves(&self) -> Vec<Option<Coordinate>> {
+        self.redo_stack
+            .iter()
+            .rev()
+            .map(|node| node.get_parent().unwrap().key_of(node).unwrap())
+            .collect()
+    }
+
+    /// Returns the moves available to the side to move, in row-major
+    /// order. Empty when the side must pass or the game is over.
+    pub fn legal_moves(&self) -> Vec<Coordinate> {
+        if self.ending.is_some() {
+            return Vec::new();
+        }
+
+        Coordinate::iter_all()
+            .filter(|&coord| self.current.get_child(Some(coord)).is_some())
+            .collect()
+    }
+
+    /// Returns the number of legal moves for black and white on the
+    /// current board, whoever is to move.
+    pub fn mobility(&self) -> (usize, usize) {
+        let board = &self.current.board;
+        (board.mobility(Disk::Black), board.mobility(Disk::White))
+    }
+
+    /// Suggests a move for the side to move: a corner if one is
+    /// available, otherwise the move flipping the most disks.
+    pub fn hint(&self) -> Option<Coordinate> {
+        let board = &self.current.board;
+        let disk = self.current.turn.to_disk();
+
+        let mut best = None;
+        let mut best_score = 0;
+        for coord in self.legal_moves() {
+            let child = self.current.get_child(Some(coord)).unwrap();
+            let flips = child.board.count(disk) - board.count(disk) - 1;
+            let score = match coord.indices() {
+                (0, 0) | (0, 7) | (7, 0) | (7, 7) => flips + 100,
+                _ => flips,
+            };
+            if best.is_none() || score > best_score {
+                best = Some(coord);
+                best_score = score;
+            }
+        }
+
+        best
+    }
+
+    /// Returns each legal move with its minimax score for the side to
+    /// move, searched `depth` plies deep, best first.
+    pub fn candidate_moves(&self, depth: u8) -> Vec<(Coordinate, i32)> {
+        let disk = self.current.turn.to_disk();
+        search::score_moves(&self.current.board, disk, depth)
+    }
+
+    /// Counts the positions reached after `depth` plies from the current
+    /// one with `Board::perft`, passes included.
+    pub fn perft_from_current(&self, depth: u8) -> u64 {
+        let disk = self.current.turn.to_disk();
+        self.current.board.perft(disk, depth)
+    }
+
+    /// Returns every board from the root to the current position,
+    /// numbered by ply. Boards after a pass repeat the one before.
+    pub fn history_boards(&self) -> Vec<(usize, Board)> {
+        let mut boards = Vec::new();
+        let mut node = Rc::clone(&self.current);
+        boards.push(node.board.clone());
+        while let Some(parent) = node.get_parent() {
+            boards.push(parent.board.clone());
+            node = parent;
+        }
+        boards.reverse();
+
+        boards.into_iter().enumerate().collect()
+    }
+
+    /// Returns the board after `n` plies on the way to the current
+    /// position, without moving there, or `None` past `move_number`.
+    pub fn get_board_at_move(&self, n: u32) -> Option<Board> {
+        let mut steps = self.move_number().checked_sub(n)?;
+        let mut node = Rc::clone(&self.current);
+        while steps > 0 {
+            node = node.get_parent()?;
+            steps -= 1;
+        }
+
+        Some(node.board.clone())
+    }
+
+    /// Returns how many plies, passes included, lead from the start
+    /// position to the current one.
+    pub fn move_number(&self) -> u32 {
+        let mut number = 0;
+        let mut node = Rc::clone(&self.current);
+        while let Some(parent) = node.get_parent() {
+            number += 1;
+            node = parent;
+        }
+
+        number
+    }
+
+    /// Returns how many disks have been placed on the way to the current
+    /// position, leaving out passes.
+    pub fn count_total_moves_played(&self) -> u32 {
+        let history = self.move_history();
+        history.iter().filter(|coord| coord.is_some()).count() as u32
+    }
+
+    /// Returns how many passes, forced ones included, lead to the current
+    /// position.
+    pub fn count_total_passes(&self) -> u32 {
+        let history = self.move_history();
+        history.iter().filter(|coord| coord.is_none()).count() as u32
+    }
+
+    /// Same as `move_number`, under the name used by PGN tools.
+    pub fn half_move_clock(&self) -> u32 {
+        self.move_number()
+    }
+
+    pub fn tree_size(&self) -> usize {
+        self.root.tree_size(&mut HashSet::new())
+    }
+
+    pub fn status(&self) -> GameStatus {
+        if let Some(ending) = self.ending {
+            return ending;
+        }
+        if !self.current.has_any_child() {
+            let board = &self.current.board;
+            let black = board.count(Disk::Black) as u8;
+            let white = board.count(Disk::White) as u8;
+            return GameStatus::GameOver(black, white);
+        }
+
+        if let Some(parent) = self.current.get_parent() {
+            if let Some(_node) = parent.get_child(None) {
+                return GameStatus::PassBack(parent.turn);
+            }
+        }
+
+        GameStatus::Continue(self.current.turn)
+    }
+
+    /// Tells why the game ended, or `None` while it goes on.
+    pub fn game_over_reason(&self) -> Option<GameOverReason> {
+        match self.status() {
+            GameStatus::GameOver(black, white) if black + white == 64 => {
+                Some(GameOverReason::BoardFull)
+            }
+            GameStatus::GameOver(_, _) => Some(GameOverReason::NoMoves),
+            GameStatus::Resigned(Side::Dark) => {
+                Some(GameOverReason::BlackResigned)
+            }
+            GameStatus::Resigned(Side::Light) => {
+                Some(GameOverReason::WhiteResigned)
+            }
+            GameStatus::DrawAgreed => Some(GameOverReason::DrawAgreed),
+            _ => None,
+        }
+    }
+
     pub fn action(&mut self, command: Command) {
         match command {
             Command::Init => self.init(),
             Command::Quit => self.quit(),
             Command::Undo => self.undo(),
-            Command::Move(coord) => {
-                self.try_move(coord);
+            Command::Redo => {
+                self.redo_n(1);
             }
+            Command::Pass => match self.pass() {
+                Ok(_) => self.update_status(None),
+                Err(PassError::MovesAvailable) => {
+                    self.update_status(Some(GameMessage::CantPass))
+                }
+                Err(PassError::GameOver) => (),
+            },
+            Command::Move(coord) => match self.place(coord) {
+                Ok(_) => self.update_status(None),
+                Err(PlaceError::IllegalMove(err)) => self
+                    .update_status(Some(GameMessage::CantPlace(coord, err))),
+                Err(PlaceError::GameOver) => (),
+            },
+            Command::Resign(side) => {
+                if !self.status().is_over() {
+                    self.ending = Some(GameStatus::Resigned(side));
+                    self.draw_offer = None;
+                    self.redo_stack.clear();
+                    self.update_status(None);
+                }
+            }
+            Command::OfferDraw => {
+                if !self.status().is_over() {
+                    self.draw_offer = Some(self.current.turn);
+                    self.update_status(Some(GameMessage::DrawOffered));
+                }
+            }
+            Command::AcceptDraw => match self.draw_offer {
+                Some(side)
+                    if side != self.current.turn
+                        && !self.status().is_over() =>
+                {
+                    self.ending = Some(GameStatus::DrawAgreed);
+                    self.draw_offer = None;
+                    self.redo_stack.clear();
+                    self.update_status(None);
+                }
+                _ => self.update_status(Some(GameMessage::NoDrawOffer)),
+            },
         }
     }
 
-    fn init(&mut self) {
-        self.current = Rc::clone(&self.root);
-        self.extend_tree();
+    /// Returns the side whose draw offer waits for the other side to
+    /// accept it on its turn.
+    pub fn draw_offer(&self) -> Option<Side> {
+        self.draw_offer
+    }
+
+    /// Runs the commands in order and returns the status text after each,
+    /// stopping early once the game is over.
+    pub fn apply_command_batch(
+        &mut self,
+        commands: &[Command],
+    ) -> Vec<String> {
+        let mut statuses = Vec::with_capacity(commands.len());
+        for &command in commands {
+            self.action(command);
+            statuses.push(self.status_text());
+            if self.status().is_over() {
+                break;
+            }
+        }
 
-        self.update_status(Some("Game start!"));
+        statuses
     }
 
-    fn try_move(&mut self, coord: Coordinate) {
-        if !self.current.has_any_child() {
-            return;
+    /// Lets the strategies play both sides until the game is over, and
+    /// returns the final status. Stops early, returning the status at that
+    /// point, if a strategy passes or picks an illegal move while it has a
+    /// legal one.
+    pub fn simulate_to_end(
+        &mut self,
+        dark: &mut dyn Strategy,
+        light: &mut dyn Strategy,
+    ) -> GameStatus {
+        loop {
+            let status = self.status();
+            if status.is_over() {
+                return status;
+            }
+
+            let side = self.turn();
+            let moves = self.legal_moves();
+            let board = self.current_board();
+            let choice = match side {
+                Side::Dark => dark.choose_move(board, side, &moves),
+                Side::Light => light.choose_move(board, side, &moves),
+            };
+            let played = match choice {
+                Some(coord) => self.place(coord).is_ok(),
+                None => self.pass().is_ok(),
+            };
+            if !played {
+                return status;
+            }
+            self.update_status(None);
         }
+    }
 
-        if let Some(node) = self.current.get_child(Some(coord)) {
-            self.current = node;
-            self.extend_tree();
+    /// Plays a move and returns the resulting status. Unless `auto_pass`
+    /// is turned off, the opponent passes automatically if it has no
+    /// legal move.
+    pub fn place(
+        &mut self,
+        coord: Coordinate,
+    ) -> Result<GameStatus, PlaceError> {
+        if self.status().is_over() {
+            return Err(PlaceError::GameOver);
+        }
+
+        let node = match self.current.get_child(Some(coord)) {
+            Some(node) => node,
+            None => {
+                // extend_tree gives a position a child for each legal move
+                // before it is played from, so only illegal ones get here.
+                let disk = self.current.turn.to_disk();
+                let err = match self.current.board.try_move(coord, disk) {
+                    Err(err) => err,
+                    Ok(_) => {
+                        unreachable!("legal move {} without a child", coord)
+                    }
+                };
+                return Err(PlaceError::IllegalMove(err));
+            }
+        };
+        // A node shared with another line keeps the parent it was last
+        // entered from, so that undo retraces the moves actually played.
+        node.set_parent(Rc::clone(&self.current));
+        self.decline_draw();
+        self.current = node;
+        self.redo_stack.clear();
+        self.extend_tree();
+        self.check_double_pass();
+        if self.auto_pass {
+            self.skip_pass();
+        }
+        self.sync_history();
+
+        Ok(self.status())
+    }
+
+    /// Passes the turn, which is only allowed when the side to move has
+    /// no legal move.
+    pub fn pass(&mut self) -> Result<GameStatus, PassError> {
+        if self.status().is_over() {
+            return Err(PassError::GameOver);
+        }
 
-            if let Some(node) = self.current.get_child(None) {
+        match self.current.get_child(None) {
+            Some(node) => {
+                self.decline_draw();
                 self.current = node;
-                self.extend_tree();
+                self.redo_stack.clear();
+                self.sync_history();
+                Ok(self.status())
+            }
+            None => Err(PassError::MovesAvailable),
+        }
+    }
 
-                if let Some(_node) = self.current.get_child(None) {
-                    self.current.remove_child(None);
-                    self.current = self.current.get_parent().unwrap();
-                    self.current.remove_child(None);
-                    self.update_status(None);
-                    return;
-                }
+    /// Takes back up to `n` plies, stopping at the root, and returns how
+    /// many were undone. When `auto_pass` is on, a forced pass is taken
+    /// back together with the move before it and is not counted. Taking
+    /// back a resignation or an agreed draw counts as one ply.
+    pub fn undo_n(&mut self, n: usize) -> usize {
+        if n == 0 {
+            return 0;
+        }
+
+        self.draw_offer = None;
+        let undone = match self.ending.take() {
+            Some(_) => 1 + self.step_back(n.saturating_sub(1)),
+            None => self.step_back(n),
+        };
+        if undone > 0 {
+            self.sync_history();
+            self.update_status(Some(GameMessage::Undo));
+        } else {
+            self.update_status(Some(GameMessage::CantUndo));
+        }
+
+        undone
+    }
+
+    /// Replays up to `n` plies taken back by undo and returns how many
+    /// were redone. Playing a new move forgets them.
+    pub fn redo_n(&mut self, n: usize) -> usize {
+        let mut redone = 0;
+        while redone < n {
+            match self.redo_stack.pop() {
+                Some(node) => self.current = node,
+                None => break,
             }
-            self.update_status(None);
+            redone += 1;
+        }
+
+        if redone > 0 {
+            self.sync_history();
+            self.update_status(Some(GameMessage::Redo));
         } else {
-            self.update_status(Some("Can't place there!"));
+            self.update_status(Some(GameMessage::NothingToRedo));
         }
+
+        redone
     }
 
-    fn quit(&self) {
-        () // do nothing now
+    /// Returns a fresh engine that starts from the same position.
+    pub fn new_game(&self) -> Engine {
+        Engine::from_board(self.root.board.clone(), self.root.turn)
     }
 
-    fn undo(&mut self) {
-        if let Some(parent) = self.current.get_parent() {
-            self.current = parent;
+    /// Jumps back to the starting position, keeping the explored tree.
+    pub fn rewind_to_root(&mut self) {
+        self.ending = None;
+        self.draw_offer = None;
+        self.step_back(usize::MAX);
+        self.sync_history();
+        self.update_status(None);
+    }
+
+    pub fn is_at_root(&self) -> bool {
+        Rc::ptr_eq(&self.current, &self.root)
+    }
+
+    fn init(&mut self) {
+        self.rewind_to_root();
+        self.redo_stack.clear();
+        self.root.clear_children();
+        self.transpositions.clear();
+        self.extend_tree();
+        self.check_double_pass();
+        self.sync_history();
+
+        self.update_status(Some(GameMessage::GameStart));
+    }
+
+    /// Ends the game when neither side has a legal move.
+    fn check_double_pass(&mut self) {
+        if let Some(node) = self.current.get_child(None) {
+            let parent = std::mem::replace(&mut self.current, node);
+            self.extend_tree();
+
             if let Some(_node) = self.current.get_child(None) {
-                let parent = self.current.get_parent().unwrap();
-                self.current = parent;
+                self.current.remove_child(None);
+                parent.remove_child(None);
             }
-            self.update_status(Some("Undo, and "));
-        } else {
-            self.update_status(Some("Can't undo!"));
+            self.current = parent;
         }
     }
 
-    fn update_status(&mut self, msg: Option<&str>) {
-        self.prompt.clear();
+    /// Withdraws the opponent's draw offer once the side to move plays on.
+    fn decline_draw(&mut self) {
+        if self.draw_offer != Some(self.current.turn) {
+            self.draw_offer = None;
+        }
+    }
 
-        if let Some(msg) = msg {
-            self.prompt += msg;
-            self.prompt += " ";
+    fn skip_pass(&mut self) {
+        if let Some(node) = self.current.get_child(None) {
+            self.current = node;
         }
+    }
 
-        if !self.current.has_any_child() {
-            self.prompt += "Game is over!";
-        } else {
-            if let Some(parent) = self.current.get_parent() {
-                if let Some(_node) = parent.get_child(None) {
-                    self.prompt += match parent.turn {
-                        Side::Dark => "Black passed, and ",
-                        Side::Light => "White passed, and ",
-                    };
+    fn quit(&self) {
+        () // do nothing now
+    }
+
+    /// Moves up to `n` plies towards the root, remembering them for redo.
+    fn step_back(&mut self, n: usize) -> usize {
+        let mut undone = 0;
+        while undone < n {
+            let parent = match self.current.get_parent() {
+                Some(parent) => parent,
+                None => break,
+            };
+            let node = std::mem::replace(&mut self.current, parent);
+            self.redo_stack.push(node);
+            if self.auto_pass {
+                if let Some(_node) = self.current.get_child(None) {
+                    if let Some(parent) = self.current.get_parent() {
+                        self.current = parent;
+                    }
                 }
             }
-            self.prompt += match self.current.turn {
-                Side::Dark => "Black's turn.",
-                Side::Light => "White's turn.",
-            };
+            undone += 1;
+        }
+
+        undone
+    }
+
+    fn undo(&mut self) {
+        self.undo_n(1);
+    }
+
+    fn sync_history(&self) {
+        let history = match &self.history {
+            Some(history) => history,
+            None => return,
+        };
+
+        let depth = self.move_history().len();
+        history.truncate(depth);
+
+        let mut plies = Vec::new();
+        let mut node = Rc::clone(&self.current);
+        for _ in history.len()..depth {
+            let parent = node.get_parent().unwrap();
+            plies.push((parent.key_of(&node).unwrap(), node.board.clone()));
+            node = parent;
         }
+        for (coord, board) in plies.into_iter().rev() {
+            history.record(coord, board);
+        }
+    }
+
+    fn update_status(&mut self, message: Option<GameMessage>) {
+        self.message = message;
+        self.prompt = self.lang.prompt(message, &self.status_text());
     }
 
-    fn extend_tree(&self) {
+    fn extend_tree(&mut self) {
         if self.current.has_any_child() {
             return;
         }
 
-        let board = &self.current.board;
-        let disk = self.current.turn.to_disk();
-        let next_turn = change_turn(self.current.turn);
-
-        for col in 'a'..='h' {
-            for row in 1..=8 {
-                let coord = Coordinate::new(col, row);
-                if let Ok(board) = board.try_move(coord, disk) {
-                    self.current.insert_child(
-                        Some(coord),
-                        Rc::new(Node::new(board, next_turn)),
-                    );
-                    self.current
-                        .get_child(Some(coord))
-                        .unwrap()
-                        .set_parent(Rc::clone(&self.current));
-                }
+        let current = Rc::clone(&self.current);
+        let disk = current.turn.to_disk();
+        let next_turn = change_turn(current.turn);
+
+        for coord in Coordinate::iter_all() {
+            if !current.board.is_valid_move(coord, disk) {
+                continue;
+            }
+            if let Ok(board) = current.board.try_move(coord, disk) {
+                let node = self.transposition(board, next_turn);
+                self.current.insert_child(Some(coord), node);
             }
         }
 
@@ -162,26 +739,66 @@ impl Engine {
                 .set_parent(Rc::clone(&self.current));
         }
     }
+
+    /// Returns the node already made for `board` with `turn` to move, or
+    /// a new child of the current node that is remembered for next time.
+    fn transposition(&mut self, board: Board, turn: Side) -> Rc<Node> {
+        let hash = board.zobrist_hash_with_side(turn);
+        let found = self
+            .transpositions
+            .get(&hash)
+            .and_then(|node| node.upgrade())
+            .filter(|node| node.board == board && node.turn == turn);
+        if let Some(node) = found {
+            return node;
+        }
+
+        let node = Rc::new(Node::new(board, turn));
+        node.set_parent(Rc::clone(&self.current));
+        self.transpositions.insert(hash, Rc::downgrade(&node));
+
+        node
+    }
+}
+
+impl Default for Engine {
+    fn default() -> Engine {
+        Engine::new()
+    }
 }
 
 // ---------------------------------------------------------------------
 
 #[derive(Clone, Copy, Debug, PartialEq)]
-enum Side {
+pub enum Side {
     Dark,
     Light,
 }
 
 impl Side {
-    fn to_disk(&self) -> Disk {
+    pub fn to_disk(&self) -> Disk {
         match self {
             Side::Dark => Disk::Black,
             Side::Light => Disk::White,
         }
     }
+
+    /// Returns the English name of the side, "Black" or "White".
+    pub fn label(self) -> &'static str {
+        match self {
+            Side::Dark => "Black",
+            Side::Light => "White",
+        }
+    }
 }
 
-fn change_turn(side: Side) -> Side {
+impl fmt::Display for Side {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.label())
+    }
+}
+
+pub fn change_turn(side: Side) -> Side {
     match side {
         Side::Dark => Side::Light,
         Side::Light => Side::Dark,
@@ -227,10 +844,35 @@ impl Node {
         }
     }
 
+    fn key_of(&self, child: &Rc<Node>) -> Option<Option<Coordinate>> {
+        let children = self.children.borrow();
+        children
+            .iter()
+            .find(|(_, node)| Rc::ptr_eq(node, child))
+            .map(|(&coord, _)| coord)
+    }
+
     fn remove_child(&self, coord: Option<Coordinate>) {
         self.children.borrow_mut().remove(&coord);
     }
 
+    fn clear_children(&self) {
+        self.children.borrow_mut().clear();
+    }
+
+    /// Counts the nodes not yet in `seen`, so that a node shared by
+    /// transposed lines is counted once.
+    fn tree_size(&self, seen: &mut HashSet<*const Node>) -> usize {
+        if !seen.insert(self as *const Node) {
+            return 0;
+        }
+        let children = self.children.borrow();
+        1 + children
+            .values()
+            .map(|node| node.tree_size(seen))
+            .sum::<usize>()
+    }
+
     fn has_any_child(&self) -> bool {
         self.num_of_children() > 0
     }
@@ -245,10 +887,24 @@ impl Node {
 #[cfg(test)]
 mod tests {
     use super::change_turn;
-    use super::{Board, Coordinate, Disk};
-    use super::{Engine, Node, Side};
+    use super::{Board, Coordinate, Disk, MoveErr};
+    use super::{Command, Engine, GameOverReason, GameStatus, Node};
+    use super::{PassError, PlaceError, Side};
+    use crate::messages::Lang;
+    use crate::search;
+    use crate::strategy::RandomStrategy;
     use std::rc::Rc;
 
+    /// Positions reached from the start at depths 1 to 5.
+    const PERFT_START: [u64; 5] = [4, 12, 56, 244, 1396];
+    /// The same after any of black's first moves, which are symmetric,
+    /// so a quarter of the next depth from the start.
+    const PERFT_AFTER_F5: [u64; 4] = [3, 14, 61, 349];
+
+    fn first_legal_move(engine: &Engine) -> Option<Coordinate> {
+        engine.legal_moves().first().copied()
+    }
+
     #[test]
     fn node_operations() {
         let mut board = Board::new();
@@ -271,12 +927,12 @@ mod tests {
         let child = parent.get_child(Some(coord)).unwrap();
         let output = "\
 ........ ........ ........ ...ox... ...xxx.. ........ ........ ........ ";
-        assert_eq!(child.board.to_string(), output);
+        assert_eq!(child.board.render_compact(), output);
 
         let parent = child.get_parent().unwrap();
         let output = "\
 ........ ........ ........ ...ox... ...xo... ........ ........ ........ ";
-        assert_eq!(parent.board.to_string(), output);
+        assert_eq!(parent.board.render_compact(), output);
 
         assert!(parent.get_parent().is_none());
         assert!(parent.has_any_child());
@@ -285,7 +941,7 @@ mod tests {
 
     #[test]
     fn engine_extend_tree() {
-        let engine = Engine::new();
+        let mut engine = Engine::new();
         engine.extend_tree();
         assert_eq!(engine.current.num_of_children(), 4);
 
@@ -293,24 +949,689 @@ mod tests {
         let node = engine.current.get_child(Some(coord)).unwrap();
         let output = "\
 ........ ........ ........ ..xxx... ...xo... ........ ........ ........ ";
-        assert_eq!(node.board.to_string(), output);
+        assert_eq!(node.board.render_compact(), output);
 
         let coord = Coordinate::new('d', 3);
         let node = engine.current.get_child(Some(coord)).unwrap();
         let output = "\
 ........ ........ ...x.... ...xx... ...xo... ........ ........ ........ ";
-        assert_eq!(node.board.to_string(), output);
+        assert_eq!(node.board.render_compact(), output);
 
         let coord = Coordinate::new('e', 6);
         let node = engine.current.get_child(Some(coord)).unwrap();
         let output = "\
 ........ ........ ........ ...ox... ...xx... ....x... ........ ........ ";
-        assert_eq!(node.board.to_string(), output);
+        assert_eq!(node.board.render_compact(), output);
 
         let coord = Coordinate::new('f', 5);
         let node = engine.current.get_child(Some(coord)).unwrap();
         let output = "\
 ........ ........ ........ ...ox... ...xxx.. ........ ........ ........ ";
-        assert_eq!(node.board.to_string(), output);
+        assert_eq!(node.board.render_compact(), output);
+    }
+
+    #[test]
+    fn engine_init_releases_old_tree() {
+        let mut engine = Engine::new();
+        engine.action(Command::Init);
+        let initial_size = engine.tree_size();
+        assert_eq!(initial_size, 5);
+
+        for _ in 0..30 {
+            match first_legal_move(&engine) {
+                Some(coord) => engine.action(Command::Move(coord)),
+                None => break,
+            }
+        }
+        assert!(engine.tree_size() > initial_size);
+
+        engine.action(Command::Init);
+        assert_eq!(engine.tree_size(), initial_size);
+        assert!(Rc::ptr_eq(&engine.current, &engine.root));
+    }
+
+    #[test]
+    fn engine_place() {
+        let mut engine = Engine::new();
+        engine.action(Command::Init);
+        assert_eq!(engine.status(), GameStatus::Continue(Side::Dark));
+
+        let result = engine.place(Coordinate::new('f', 5));
+        assert_eq!(result, Ok(GameStatus::Continue(Side::Light)));
+
+        let result = engine.place(Coordinate::new('f', 5));
+        assert_eq!(result, Err(PlaceError::IllegalMove(MoveErr::NotEmpty)));
+        let result = engine.place(Coordinate::new('a', 1));
+        let err = MoveErr::NoDiskFlipped;
+        assert_eq!(result, Err(PlaceError::IllegalMove(err)));
+        assert_eq!(engine.move_history().len(), 1);
+    }
+
+    #[test]
+    fn engine_place_pass_and_game_over() {
+        let board: Board = "\
+xo.....x .......x .......x .......x .......x .......x .......o ........"
+            .parse()
+            .unwrap();
+        let mut engine = Engine::from_board(board, Side::Dark);
+        engine.action(Command::Init);
+
+        let result = engine.place(Coordinate::new('c', 1));
+        assert_eq!(result, Ok(GameStatus::PassBack(Side::Light)));
+
+        let result = engine.place(Coordinate::new('h', 8));
+        assert_eq!(result, Ok(GameStatus::GameOver(11, 0)));
+
+        let result = engine.place(Coordinate::new('d', 1));
+        assert_eq!(result, Err(PlaceError::GameOver));
+    }
+
+    #[test]
+    fn engine_japanese_prompt() {
+        let board: Board = "\
+xo.....x .......x .......x .......x .......x .......x .......o ........"
+            .parse()
+            .unwrap();
+        let mut engine = Engine::from_board(board, Side::Dark);
+        engine.set_lang(Lang::Japanese);
+        engine.action(Command::Init);
+        assert_eq!(engine.prompt, "ゲーム開始！黒の番です。");
+
+        engine.action(Command::Move(Coordinate::new('c', 1)));
+        assert_eq!(engine.prompt, "白はパスしました。黒の番です。");
+        assert_eq!(engine.message(), None);
+
+        engine.set_name(Side::Light, "Bob");
+        engine.action(Command::Move(Coordinate::new('h', 8)));
+        assert_eq!(engine.prompt, "ゲーム終了：黒の勝ち 11–0。");
+        assert_eq!(engine.name(Side::Dark), "黒");
+        assert!(engine.is_named(Side::Light) && !engine.is_named(Side::Dark));
+    }
+
+    #[test]
+    fn engine_explicit_pass() {
+        let mut engine = Engine::new();
+        engine.action(Command::Init);
+        engine.action(Command::Pass);
+        assert_eq!(engine.prompt, "Can't pass! Black's turn.");
+        assert!(engine.move_history().is_empty());
+
+        let board: Board = "\
+ox...... ........ ........ ........ ........ ........ ........ ........"
+            .parse()
+            .unwrap();
+        let mut engine = Engine::from_board(board, Side::Dark);
+        engine.action(Command::Init);
+        engine.action(Command::Pass);
+        assert_eq!(engine.move_history(), vec![None]);
+        assert_eq!(engine.status(), GameStatus::PassBack(Side::Dark));
+
+        engine.action(Command::Undo);
+        assert!(engine.move_history().is_empty());
+    }
+
+    #[test]
+    fn engine_pass_rejected_when_moves_exist() {
+        let mut engine = Engine::new();
+        engine.action(Command::Init);
+        assert_eq!(engine.pass(), Err(PassError::MovesAvailable));
+        assert!(engine.move_history().is_empty());
+    }
+
+    #[test]
+    fn engine_pass_without_auto_pass() {
+        let board: Board = "\
+xo.....x .......x .......x .......x .......x .......x .......o ........"
+            .parse()
+            .unwrap();
+        let mut engine = Engine::from_board(board, Side::Dark);
+        engine.auto_pass = false;
+        engine.action(Command::Init);
+
+        let status = engine.place(Coordinate::new('c', 1));
+        assert_eq!(status, Ok(GameStatus::Continue(Side::Light)));
+        assert_eq!(engine.move_history(), vec![Some(Coordinate::new('c', 1))]);
+
+        assert_eq!(engine.pass(), Ok(GameStatus::PassBack(Side::Light)));
+        let coord = Coordinate::new('h', 8);
+        assert_eq!(engine.place(coord), Ok(GameStatus::GameOver(11, 0)));
+        assert_eq!(engine.pass(), Err(PassError::GameOver));
+
+        engine.action(Command::Undo);
+        engine.action(Command::Undo);
+        assert_eq!(engine.move_history(), vec![Some(Coordinate::new('c', 1))]);
+    }
+
+    #[test]
+    fn engine_apply_command_batch() {
+        let mut engine = Engine::new();
+        let statuses = engine.apply_command_batch(&[
+            Command::Init,
+            Command::Move(Coordinate::new('f', 5)),
+            Command::Move(Coordinate::new('d', 6)),
+            Command::Move(Coordinate::new('c', 3)),
+        ]);
+        assert_eq!(
+            statuses,
+            vec![
+                "Black's turn.",
+                "White's turn.",
+                "Black's turn.",
+                "White's turn."
+            ]
+        );
+
+        let board: Board = "\
+xo...... ........ ........ ........ ........ ........ ........ ........"
+            .parse()
+            .unwrap();
+        let mut engine = Engine::from_board(board, Side::Dark);
+        let statuses = engine.apply_command_batch(&[
+            Command::Init,
+            Command::Move(Coordinate::new('c', 1)),
+            Command::Undo,
+        ]);
+        assert_eq!(
+            statuses,
+            vec!["Black's turn.", "Game over: Black wins 3–0."]
+        );
+    }
+
+    #[test]
+    fn engine_simulate_to_end() {
+        for seed in 0..5 {
+            let mut engine = Engine::new();
+            engine.action(Command::Init);
+            let mut dark = RandomStrategy::with_seed(seed);
+            let mut light = RandomStrategy::with_seed(seed + 100);
+            match engine.simulate_to_end(&mut dark, &mut light) {
+                GameStatus::GameOver(black, white) => {
+                    let board = engine.current_board();
+                    assert!(
+                        black + white == 64
+                            || (board.mobility(Disk::Black) == 0
+                                && board.mobility(Disk::White) == 0)
+                    );
+                    assert_eq!(board.count(Disk::Black), black as usize);
+                    assert_eq!(board.count(Disk::White), white as usize);
+                }
+                status => panic!("game not over: {:?}", status),
+            }
+            assert!(engine.prompt.starts_with("Game over"));
+        }
+    }
+
+    #[test]
+    fn engine_undo_n() {
+        let mut engine = Engine::new();
+        engine.action(Command::Init);
+        assert_eq!(engine.undo_n(1), 0);
+        assert_eq!(engine.prompt, "Can't undo! Black's turn.");
+
+        for _ in 0..2 {
+            let coord = first_legal_move(&engine).unwrap();
+            engine.action(Command::Move(coord));
+        }
+        assert_eq!(engine.undo_n(2), 2);
+        assert!(engine.move_history().is_empty());
+
+        for _ in 0..3 {
+            let coord = first_legal_move(&engine).unwrap();
+            engine.action(Command::Move(coord));
+        }
+        assert_eq!(engine.undo_n(5), 3);
+        assert!(engine.move_history().is_empty());
+    }
+
+    #[test]
+    fn engine_redo_n() {
+        let mut engine = Engine::new();
+        engine.action(Command::Init);
+        assert_eq!(engine.redo_n(1), 0);
+        assert_eq!(engine.prompt, "Nothing to redo! Black's turn.");
+
+        for _ in 0..3 {
+            let coord = first_legal_move(&engine).unwrap();
+            engine.action(Command::Move(coord));
+        }
+        let history = engine.move_history();
+        let board = engine.current_board().clone();
+
+        assert_eq!(engine.undo_n(2), 2);
+        assert_eq!(engine.redo_moves(), history[1..].to_vec());
+        assert_eq!(engine.redo_n(1), 1);
+        assert_eq!(engine.move_history(), history[..2].to_vec());
+        assert_eq!(engine.redo_n(5), 1);
+        assert_eq!(engine.move_history(), history);
+        assert!(engine.redo_moves().is_empty());
+        assert_eq!(*engine.current_board(), board);
+
+        engine.rewind_to_root();
+        assert_eq!(engine.redo_n(3), 3);
+        assert_eq!(engine.move_history(), history);
+
+        engine.action(Command::Undo);
+        let coord = first_legal_move(&engine).unwrap();
+        engine.action(Command::Move(coord));
+        assert_eq!(engine.redo_n(1), 0);
+    }
+
+    #[test]
+    fn engine_undo_n_over_pass() {
+        let board: Board = "\
+xo.....x .......x .......x .......x .......x .......x .......o ........"
+            .parse()
+            .unwrap();
+        let c1 = Coordinate::new('c', 1);
+        let h8 = Coordinate::new('h', 8);
+
+        let mut engine = Engine::from_board(board.clone(), Side::Dark);
+        engine.action(Command::Init);
+        engine.action(Command::Move(c1));
+        engine.action(Command::Move(h8));
+        assert_eq!(engine.move_history(), vec![Some(c1), None, Some(h8)]);
+        assert_eq!(engine.undo_n(2), 2);
+        assert!(engine.move_history().is_empty());
+        assert_eq!(engine.redo_n(2), 2);
+        assert_eq!(engine.move_history(), vec![Some(c1), None, Some(h8)]);
+
+        let mut engine = Engine::from_board(board, Side::Dark);
+        engine.auto_pass = false;
+        engine.action(Command::Init);
+        engine.action(Command::Move(c1));
+        engine.action(Command::Pass);
+        engine.action(Command::Move(h8));
+        assert_eq!(engine.undo_n(2), 2);
+        assert_eq!(engine.move_history(), vec![Some(c1)]);
+    }
+
+    #[test]
+    fn engine_move_number() {
+        let mut engine = Engine::new();
+        assert_eq!(engine.move_number(), 0);
+        engine.action(Command::Init);
+        assert_eq!(engine.move_number(), 0);
+
+        let coord = first_legal_move(&engine).unwrap();
+        engine.action(Command::Move(coord));
+        assert_eq!(engine.move_number(), 1);
+        for _ in 0..3 {
+            let coord = first_legal_move(&engine).unwrap();
+            engine.action(Command::Move(coord));
+        }
+        assert_eq!(engine.move_number(), 4);
+        assert_eq!(engine.half_move_clock(), 4);
+
+        engine.action(Command::Undo);
+        assert_eq!(engine.move_number(), 3);
+        engine.undo_n(3);
+        assert_eq!(engine.move_number(), 0);
+    }
+
+    #[test]
+    fn engine_rewind_to_root() {
+        let mut engine = Engine::new();
+        engine.action(Command::Init);
+        assert!(engine.is_at_root());
+        let initial = engine.current_board().clone();
+
+        for _ in 0..5 {
+            let coord = first_legal_move(&engine).unwrap();
+            engine.action(Command::Move(coord));
+        }
+        assert!(!engine.is_at_root());
+        let size = engine.tree_size();
+
+        engine.rewind_to_root();
+        assert!(engine.is_at_root());
+        assert_eq!(*engine.current_board(), initial);
+        assert_eq!(engine.tree_size(), size);
+        assert_eq!(engine.prompt, "Black's turn.");
+    }
+
+    #[test]
+    fn engine_history_boards() {
+        let mut engine = Engine::new();
+        engine.action(Command::Init);
+        let initial = engine.current_board().clone();
+        assert_eq!(engine.history_boards(), vec![(0, initial.clone())]);
+
+        for _ in 0..4 {
+            let coord = first_legal_move(&engine).unwrap();
+            engine.action(Command::Move(coord));
+        }
+        let boards = engine.history_boards();
+        assert_eq!(boards.len(), engine.move_history().len() + 1);
+        assert_eq!(boards[0], (0, initial));
+        assert_eq!(boards[4], (4, engine.current_board().clone()));
+    }
+
+    #[test]
+    fn engine_game_over_reason() {
+        let mut engine = Engine::new();
+        engine.action(Command::Init);
+        assert_eq!(engine.game_over_reason(), None);
+
+        let board: Board = "xxxxoooo ".repeat(8).parse().unwrap();
+        let mut engine = Engine::from_board(board, Side::Dark);
+        engine.action(Command::Init);
+        assert_eq!(engine.game_over_reason(), Some(GameOverReason::BoardFull));
+
+        let board: Board = "\
+ox...... ........ ........ ........ ........ ........ ........ ........"
+            .parse()
+            .unwrap();
+        let mut engine = Engine::from_board(board, Side::Dark);
+        engine.action(Command::Init);
+        assert_eq!(engine.game_over_reason(), None);
+        engine.action(Command::Pass);
+        engine.action(Command::Move(Coordinate::new('c', 1)));
+        assert_eq!(engine.game_over_reason(), Some(GameOverReason::NoMoves));
+    }
+
+    #[test]
+    fn engine_mobility() {
+        let board: Board = "\
+xo.....x .......x .......x .......x .......x .......x .......o ........"
+            .parse()
+            .unwrap();
+        let black = board.valid_moves(Disk::Black).len();
+        let white = board.valid_moves(Disk::White).len();
+        let mut engine = Engine::from_board(board, Side::Dark);
+        engine.action(Command::Init);
+        assert_eq!(engine.mobility(), (black, white));
+        assert_eq!(engine.mobility(), (2, 0));
+        assert_eq!(engine.legal_moves().len(), 2);
+
+        engine.action(Command::Move(Coordinate::new('c', 1)));
+        assert_eq!(engine.mobility(), (1, 0));
+        engine.action(Command::Move(Coordinate::new('h', 8)));
+        assert_eq!(engine.mobility(), (0, 0));
+    }
+
+    #[test]
+    fn engine_hint() {
+        let mut engine = Engine::new();
+        assert_eq!(engine.hint(), None);
+        engine.action(Command::Init);
+        for _ in 0..10 {
+            let hint = engine.hint().unwrap();
+            assert!(engine.legal_moves().contains(&hint));
+            engine.action(Command::Move(hint));
+        }
+
+        let board: Board = "\
+.ox..... ........ ........ xooo.... ........ ........ ........ ........"
+            .parse()
+            .unwrap();
+        let mut engine = Engine::from_board(board, Side::Dark);
+        engine.action(Command::Init);
+        let expected = vec![Coordinate::new('a', 1), Coordinate::new('e', 4)];
+        assert_eq!(engine.legal_moves(), expected);
+        assert_eq!(engine.hint(), Some(Coordinate::new('a', 1)));
+
+        let board: Board = "\
+ox...... ........ ........ ........ ........ ........ ........ ........"
+            .parse()
+            .unwrap();
+        let mut engine = Engine::from_board(board, Side::Dark);
+        engine.action(Command::Init);
+        assert_eq!(engine.hint(), None);
+    }
+
+    #[test]
+    fn game_status_display() {
+        let status = GameStatus::Continue(Side::Dark);
+        assert_eq!(status.to_string(), "Black's turn.");
+        let status = GameStatus::PassBack(Side::Light);
+        assert_eq!(status.to_string(), "White passed, and Black's turn.");
+        let status = GameStatus::GameOver(40, 24);
+        assert_eq!(status.to_string(), "Game over: Black wins 40–24.");
+        let status = GameStatus::GameOver(30, 34);
+        assert_eq!(status.to_string(), "Game over: White wins 34–30.");
+        let status = GameStatus::GameOver(32, 32);
+        assert_eq!(status.to_string(), "Game over: draw 32–32.");
+    }
+
+    #[test]
+    fn engine_snapshot() {
+        let mut engine = Engine::new();
+        engine.action(Command::Init);
+        for _ in 0..10 {
+            let coord = first_legal_move(&engine).unwrap();
+            engine.action(Command::Move(coord));
+        }
+
+        let snapshot = engine.snapshot();
+        let board = engine.current_board();
+        for (index, coord) in Coordinate::iter_all().enumerate() {
+            assert_eq!(snapshot[index], board.get_disk(coord));
+        }
+    }
+
+    #[test]
+    fn engine_move_history() {
+        let mut engine = Engine::new();
+        engine.action(Command::Init);
+        assert!(engine.move_history().is_empty());
+
+        let f5 = Coordinate::new('f', 5);
+        let d6 = Coordinate::new('d', 6);
+        engine.action(Command::Move(f5));
+        engine.action(Command::Move(d6));
+        assert_eq!(engine.move_history(), vec![Some(f5), Some(d6)]);
+
+        engine.action(Command::Undo);
+        assert_eq!(engine.move_history(), vec![Some(f5)]);
+    }
+
+    #[test]
+    fn side_display() {
+        assert_eq!(Side::Dark.to_string(), "Black");
+        assert_eq!(Side::Light.to_string(), "White");
+        assert_eq!(format!("{} to move", Side::Light), "White to move");
+    }
+
+    #[test]
+    fn engine_shares_transposed_positions() {
+        let play = |engine: &mut Engine, moves: &[(char, usize)]| {
+            for &(col, row) in moves {
+                engine.action(Command::Move(Coordinate::new(col, row)));
+            }
+        };
+
+        let mut engine = Engine::new();
+        engine.action(Command::Init);
+        play(&mut engine, &[('d', 3), ('c', 3), ('c', 4)]);
+        let first = Rc::clone(&engine.current);
+        engine.undo_n(3);
+        assert!(engine.is_at_root());
+
+        play(&mut engine, &[('c', 4), ('c', 3), ('d', 3)]);
+        assert!(Rc::ptr_eq(&engine.current, &first));
+        assert_eq!(engine.transcript(), "c4c3d3");
+
+        engine.undo_n(1);
+        assert_eq!(engine.transcript(), "c4c3");
+        play(&mut engine, &[('d', 3), ('e', 3)]);
+        assert_eq!(engine.transcript(), "c4c3d3e3");
+    }
+
+    #[test]
+    fn engine_tree_size_counts_transpositions_once() {
+        let play = |engine: &mut Engine, moves: &[(char, usize)]| {
+            for &(col, row) in moves {
+                engine.action(Command::Move(Coordinate::new(col, row)));
+            }
+        };
+
+        let mut engine = Engine::new();
+        engine.action(Command::Init);
+        play(&mut engine, &[('d', 3), ('c', 3), ('c', 4)]);
+        engine.undo_n(3);
+        play(&mut engine, &[('c', 4), ('c', 3)]);
+        // d3 from here reaches the node after d3 c3 c4, already counted
+        // with its children under d3.
+        assert_eq!(engine.tree_size(), 20);
+
+        play(&mut engine, &[('d', 3)]);
+        assert_eq!(engine.tree_size(), 20);
+    }
+
+    #[test]
+    fn engine_board_at_move() {
+        let mut engine = Engine::new();
+        engine.action(Command::Init);
+        let start = engine.current_board().clone();
+        assert_eq!(engine.get_board_at_move(0), Some(start.clone()));
+        assert_eq!(engine.get_board_at_move(1), None);
+
+        for &(col, row) in &[('f', 5), ('d', 6), ('c', 3)] {
+            engine.action(Command::Move(Coordinate::new(col, row)));
+        }
+        let after_f5 = start.try_move(Coordinate::new('f', 5), Disk::Black);
+        assert_eq!(engine.get_board_at_move(0), Some(start));
+        assert_eq!(engine.get_board_at_move(1), after_f5.ok());
+        let depth = engine.move_number();
+        let current = engine.current_board().clone();
+        assert_eq!(engine.get_board_at_move(depth), Some(current.clone()));
+        assert_eq!(engine.get_board_at_move(depth + 1), None);
+        assert_eq!(engine.current_board(), &current);
+    }
+
+    #[test]
+    fn engine_counts_moves_and_passes() {
+        let mut engine = Engine::new();
+        engine.action(Command::Init);
+        assert_eq!(engine.count_total_moves_played(), 0);
+        assert_eq!(engine.count_total_passes(), 0);
+        engine.action(Command::Move(Coordinate::new('f', 5)));
+        assert_eq!(engine.count_total_moves_played(), 1);
+        engine.action(Command::Move(Coordinate::new('d', 6)));
+        engine.action(Command::Undo);
+        assert_eq!(engine.count_total_moves_played(), 1);
+        assert_eq!(engine.count_total_passes(), 0);
+
+        let board: Board = "\
+xo.....x .......x .......x .......x .......x .......x .......o ........"
+            .parse()
+            .unwrap();
+        let mut engine = Engine::from_board(board, Side::Dark);
+        engine.action(Command::Init);
+        engine.action(Command::Move(Coordinate::new('c', 1)));
+        assert_eq!(engine.count_total_moves_played(), 1);
+        assert_eq!(engine.count_total_passes(), 1);
+    }
+
+    #[test]
+    fn engine_candidate_moves() {
+        let mut engine = Engine::new();
+        engine.action(Command::Init);
+        let candidates = engine.candidate_moves(2);
+        let mut moves: Vec<Coordinate> =
+            candidates.iter().map(|&(coord, _)| coord).collect();
+        moves.sort_by_key(|coord| coord.to_index());
+        assert_eq!(moves, engine.legal_moves());
+        assert!(candidates.iter().all(|&(_, score)| score.abs() < 1000));
+        assert!(candidates.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+
+        let best = search::best_move(engine.current_board(), Disk::Black, 2);
+        assert_eq!(Some(candidates[0].0), best);
+    }
+
+    #[test]
+    fn engine_resign() {
+        let mut engine = Engine::new();
+        engine.action(Command::Init);
+        engine.action(Command::Move(Coordinate::new('f', 5)));
+        let board = engine.current_board().clone();
+        engine.action(Command::Resign(Side::Light));
+        assert_eq!(engine.status(), GameStatus::Resigned(Side::Light));
+        assert!(engine.status().is_over());
+        assert_eq!(
+            engine.game_over_reason(),
+            Some(GameOverReason::WhiteResigned)
+        );
+        assert_eq!(
+            engine.prompt,
+            "Game over: White resigned, and Black wins."
+        );
+        assert_eq!(*engine.current_board(), board);
+        assert_eq!(engine.move_history().len(), 1);
+        assert!(engine.legal_moves().is_empty());
+        let d6 = Coordinate::new('d', 6);
+        assert_eq!(engine.place(d6), Err(PlaceError::GameOver));
+        assert_eq!(engine.pass(), Err(PassError::GameOver));
+
+        assert_eq!(engine.undo_n(1), 1);
+        assert_eq!(engine.status(), GameStatus::Continue(Side::Light));
+        assert_eq!(engine.move_history().len(), 1);
+        assert_eq!(engine.place(d6), Ok(GameStatus::Continue(Side::Dark)));
+
+        engine.action(Command::Resign(Side::Dark));
+        assert_eq!(engine.undo_n(0), 0);
+        assert_eq!(engine.status(), GameStatus::Resigned(Side::Dark));
+        assert_eq!(engine.undo_n(2), 2);
+        assert_eq!(engine.move_history().len(), 1);
+        engine.action(Command::Init);
+        assert_eq!(engine.status(), GameStatus::Continue(Side::Dark));
+    }
+
+    #[test]
+    fn engine_draw_offer() {
+        let mut engine = Engine::new();
+        engine.action(Command::Init);
+        engine.action(Command::AcceptDraw);
+        assert_eq!(engine.prompt, "No draw to accept! Black's turn.");
+
+        engine.action(Command::OfferDraw);
+        assert_eq!(engine.draw_offer(), Some(Side::Dark));
+        assert_eq!(engine.prompt, "Draw offered. Black's turn.");
+        engine.action(Command::AcceptDraw);
+        assert_eq!(engine.status(), GameStatus::Continue(Side::Dark));
+
+        engine.action(Command::Move(Coordinate::new('f', 5)));
+        assert_eq!(engine.draw_offer(), Some(Side::Dark));
+        engine.action(Command::Move(Coordinate::new('d', 6)));
+        assert_eq!(engine.draw_offer(), None);
+
+        engine.action(Command::OfferDraw);
+        engine.action(Command::Move(Coordinate::new('c', 3)));
+        engine.action(Command::AcceptDraw);
+        assert_eq!(engine.status(), GameStatus::DrawAgreed);
+        assert_eq!(
+            engine.game_over_reason(),
+            Some(GameOverReason::DrawAgreed)
+        );
+        assert_eq!(engine.prompt, "Game over: draw agreed.");
+        assert_eq!(engine.move_history().len(), 3);
+
+        engine.action(Command::Undo);
+        assert_eq!(engine.status(), GameStatus::Continue(Side::Light));
+        assert_eq!(engine.draw_offer(), None);
+    }
+
+    #[test]
+    fn engine_perft_from_current() {
+        let mut engine = Engine::new();
+        engine.action(Command::Init);
+        for (depth, &nodes) in (1..).zip(PERFT_START.iter()) {
+            assert_eq!(engine.perft_from_current(depth), nodes);
+        }
+        assert_eq!(engine.perft_from_current(0), 1);
+
+        engine.action(Command::Move(Coordinate::new('f', 5)));
+        assert_eq!(engine.turn(), Side::Light);
+        for (depth, &nodes) in (1..).zip(PERFT_AFTER_F5.iter()) {
+            assert_eq!(engine.perft_from_current(depth), nodes);
+        }
+
+        let board: Board = "\
+ox...... ........ ........ ........ ........ ........ ........ ........"
+            .parse()
+            .unwrap();
+        let mut engine = Engine::from_board(board, Side::Dark);
+        engine.action(Command::Init);
+        assert_eq!(engine.perft_from_current(3), 1);
     }
 }
@@ -1,7 +1,11 @@
 use crate::board::Disk;
-use crate::engine::{Command, Engine};
-use crate::position::Coordinate;
+use crate::book::OpeningBook;
+use crate::engine::{Command, Engine, GameStatus, Side};
+use crate::messages::GameMessage;
+use crate::position::{Column, Coordinate, Row};
+use crate::search;
 
+use gdk_pixbuf::Pixbuf;
 use gio::prelude::*;
 use gtk::prelude::*;
 use gtk::Application;
@@ -12,17 +16,49 @@ use gtk::TextBuffer;
 
 use std::cell::RefCell;
 use std::collections::HashMap;
+use std::path::Path;
 use std::rc::Rc;
+use std::sync::Arc;
 
-pub fn run() -> Result<(), &'static str> {
+const EMPTY_PNG: &[u8] = include_bytes!("../images/empty.png");
+const BLACK_PNG: &[u8] = include_bytes!("../images/black.png");
+const WHITE_PNG: &[u8] = include_bytes!("../images/white.png");
+
+/// Size and, optionally, position of the main window.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WindowConfig {
+    pub width: i32,
+    pub height: i32,
+    pub x: Option<i32>,
+    pub y: Option<i32>,
+}
+
+impl Default for WindowConfig {
+    fn default() -> WindowConfig {
+        WindowConfig {
+            width: 400,
+            height: 300,
+            x: None,
+            y: None,
+        }
+    }
+}
+
+/// Opens the window. With `computer` set to a side and a search depth,
+/// the computer plays that side, from `book` while the game follows it.
+pub fn run(
+    config: WindowConfig,
+    computer: Option<(Side, u8)>,
+    book: Option<Arc<OpeningBook>>,
+) -> Result<(), &'static str> {
     let app_id = Some("othello.gtk.rust");
     let application = match Application::new(app_id, Default::default()) {
         Ok(app) => app,
         Err(_) => return Err("Failed to initialize GTK application."),
     };
 
-    application.connect_activate(|app| {
-        let window = create_application_window(app);
+    application.connect_activate(move |app| {
+        let window = create_application_window(app, config);
         let images = Images::new();
         let ui = build_ui(&window, &images.empty);
 
@@ -31,33 +67,42 @@ pub fn run() -> Result<(), &'static str> {
         let game = Rc::new(RefCell::new(Game::new(disks, text, images)));
         {
             let mut game = game.borrow_mut();
-            game.engine.action(Command::Init);
-            game.render();
+            game.computer = computer;
+            game.book = book.clone();
+            game.action(Command::Init);
         }
+        play_computer_move(&game);
 
         let game_clone = Rc::clone(&game);
         ui.init_button.connect_clicked(move |_| {
-            let mut game = game_clone.borrow_mut();
-            game.engine.action(Command::Init);
-            game.render();
+            game_clone.borrow_mut().action(Command::Init);
+            play_computer_move(&game_clone);
         });
 
         let game_clone = Rc::clone(&game);
         ui.undo_button.connect_clicked(move |_| {
             let mut game = game_clone.borrow_mut();
-            game.engine.action(Command::Undo);
-            game.render();
+            game.undo_n(1);
+            while game.is_computer_turn() && !game.engine.is_at_root() {
+                game.undo_n(1);
+            }
+            // Back at the start on the computer's turn, it moves again.
+            drop(game);
+            play_computer_move(&game_clone);
         });
 
         let game_clone = Rc::clone(&game);
         ui.move_button.connect_button_press_event(move |_, button| {
-            let mut game = game_clone.borrow_mut();
+            if game_clone.borrow().is_computer_turn() {
+                return Inhibit(true);
+            }
             let (x, y) = button.get_position();
-            let col = b"abcdefgh"[x as usize / 32] as char;
-            let row = y as usize / 32 + 1;
-            let coord = Coordinate::new(col, row);
-            game.engine.action(Command::Move(coord));
-            game.render();
+            let coord = match square_at(x, y) {
+                Some(coord) => coord,
+                None => return Inhibit(true),
+            };
+            game_clone.borrow_mut().action(Command::Move(coord));
+            play_computer_move(&game_clone);
             Inhibit(true)
         });
 
@@ -69,12 +114,55 @@ pub fn run() -> Result<(), &'static str> {
     Ok(())
 }
 
+/// Searches for the computer's move off the UI thread, showing that it
+/// is thinking meanwhile, and plays it once found. A result arriving
+/// after the game has moved on, such as by a new game, is dropped.
+fn play_computer_move(game: &Rc<RefCell<Game>>) {
+    let (board, disk, depth, book, id) = {
+        let game = game.borrow();
+        let depth = match game.computer {
+            Some((_, depth)) if game.is_computer_turn() => depth,
+            _ => return,
+        };
+        let thinking = game.engine.lang().message(GameMessage::Thinking);
+        let text = format!("{}\n{}", game.buffer, thinking);
+        game.status_line.set_text(&text);
+        let board = game.engine.current_board().clone();
+        let disk = game.engine.turn().to_disk();
+        (board, disk, depth, game.book.clone(), game.actions)
+    };
+
+    let (sender, receiver) =
+        glib::MainContext::channel(glib::PRIORITY_DEFAULT);
+    search::spawn_best_move(board, disk, depth, book, move |coord| {
+        let _ = sender.send(coord);
+    });
+
+    let game = Rc::clone(game);
+    receiver.attach(None, move |coord| {
+        if game.borrow().actions == id {
+            let command = match coord {
+                Some(coord) => Command::Move(coord),
+                None => Command::Pass,
+            };
+            game.borrow_mut().action(command);
+            play_computer_move(&game);
+        }
+        glib::Continue(false)
+    });
+}
+
 pub struct Game {
     engine: Engine,
     disks: Rc<RefCell<HashMap<Coordinate, Image>>>,
     status_line: Rc<TextBuffer>,
-    images: Images,
+    pixbufs: [Option<Pixbuf>; 3],
+    drawn: Option<[Option<Disk>; 64]>,
     buffer: String,
+    events: GameEvents,
+    computer: Option<(Side, u8)>,
+    book: Option<Arc<OpeningBook>>,
+    actions: u64,
 }
 
 impl Game {
@@ -87,42 +175,155 @@ impl Game {
             engine: Engine::new(),
             disks,
             status_line: text,
-            images,
+            pixbufs: [
+                images.empty.get_pixbuf(),
+                images.black.get_pixbuf(),
+                images.white.get_pixbuf(),
+            ],
+            drawn: None,
             buffer: String::with_capacity(1024),
+            events: GameEvents::default(),
+            computer: None,
+            book: None,
+            actions: 0,
         }
     }
 
+    /// Runs a command, notifies the callbacks and redraws the board.
+    pub fn action(&mut self, command: Command) {
+        self.actions += 1;
+        self.engine.action(command);
+        self.events.update(&self.engine);
+        self.render();
+    }
+
+    /// Calls `callback` with the final disk counts when a game ends.
+    pub fn on_game_over(&mut self, callback: Box<dyn Fn(u8, u8)>) {
+        self.events.on_game_over = Some(callback);
+    }
+
+    /// Calls `callback` with the side that was forced to pass.
+    pub fn on_pass(&mut self, callback: Box<dyn Fn(Side)>) {
+        self.events.on_pass = Some(callback);
+    }
+
+    /// Tells whether the computer is to move in a game that isn't over.
+    pub fn is_computer_turn(&self) -> bool {
+        match (self.computer, self.engine.status()) {
+            (_, status) if status.is_over() => false,
+            (Some((side, _)), _) => side == self.engine.turn(),
+            (None, _) => false,
+        }
+    }
+
+    pub fn snapshot(&self) -> [Option<Disk>; 64] {
+        self.engine.snapshot()
+    }
+
+    pub fn hint(&self) -> Option<Coordinate> {
+        self.engine.hint()
+    }
+
+    /// Takes back up to `n` moves like `action` does one, and returns
+    /// how many were taken back.
+    pub fn undo_n(&mut self, n: usize) -> usize {
+        self.actions += 1;
+        let undone = self.engine.undo_n(n);
+        self.events.update(&self.engine);
+        self.render();
+
+        undone
+    }
+
+    /// Redraws the cells whose disk changed since the last render, or
+    /// every cell the first time.
     pub fn render(&mut self) {
-        let board = self.engine.current_board();
-        let mut black = 0;
-        let mut white = 0;
-
-        for col in 'a'..='h' {
-            for row in 1..=8 {
-                let coord = Coordinate::new(col, row);
-                let image = match board.get_disk(coord) {
-                    None => &self.images.empty,
-                    Some(Disk::Black) => {
-                        black += 1;
-                        &self.images.black
+        let snapshot = self.snapshot();
+        let disks = self.disks.borrow();
+        for index in changed_cells(self.drawn.as_ref(), &snapshot) {
+            let coord = Coordinate::from_index(index).unwrap();
+            let pixbuf = match snapshot[index] {
+                None => &self.pixbufs[0],
+                Some(Disk::Black) => &self.pixbufs[1],
+                Some(Disk::White) => &self.pixbufs[2],
+            };
+            if let Some(image) = disks.get(&coord) {
+                image.set_from_pixbuf(pixbuf.as_ref());
+            }
+        }
+        drop(disks);
+        self.drawn = Some(snapshot);
+
+        self.buffer = status_text(&self.engine);
+        self.status_line.set_text(&self.buffer);
+    }
+}
+
+/// Returns the square under a click at `(x, y)` on the board, or `None`
+/// past its edges.
+fn square_at(x: f64, y: f64) -> Option<Coordinate> {
+    let col = *b"abcdefgh".get(x as usize / 32)? as char;
+    Coordinate::try_new(col, y as usize / 32 + 1)
+}
+
+/// Returns the disk counts and the status shown below the board.
+pub(crate) fn status_text(engine: &Engine) -> String {
+    let board = engine.current_board();
+    let counts = engine
+        .lang()
+        .counts(board.count(Disk::Black), board.count(Disk::White));
+    format!("{}\n{}", counts, engine.prompt)
+}
+
+/// Returns the indices of the cells that differ between two snapshots,
+/// or of every cell when nothing has been drawn yet.
+fn changed_cells(
+    drawn: Option<&[Option<Disk>; 64]>,
+    snapshot: &[Option<Disk>; 64],
+) -> Vec<usize> {
+    (0..64)
+        .filter(|&index| match drawn {
+            Some(drawn) => drawn[index] != snapshot[index],
+            None => true,
+        })
+        .collect()
+}
+
+/// Callbacks for embedding applications, each called once per event even
+/// though the status stays the same until the next command.
+#[derive(Default)]
+struct GameEvents {
+    on_game_over: Option<Box<dyn Fn(u8, u8)>>,
+    on_pass: Option<Box<dyn Fn(Side)>>,
+    game_over_notified: bool,
+    pass_notified: Option<u32>,
+}
+
+impl GameEvents {
+    fn update(&mut self, engine: &Engine) {
+        match engine.status() {
+            GameStatus::GameOver(black, white) => {
+                if !self.game_over_notified {
+                    self.game_over_notified = true;
+                    if let Some(callback) = &self.on_game_over {
+                        callback(black, white);
                     }
-                    Some(Disk::White) => {
-                        white += 1;
-                        &self.images.white
+                }
+            }
+            GameStatus::PassBack(side) => {
+                self.game_over_notified = false;
+                let ply = engine.move_number();
+                if self.pass_notified != Some(ply) {
+                    self.pass_notified = Some(ply);
+                    if let Some(callback) = &self.on_pass {
+                        callback(side);
                     }
-                };
-                let pixbuf = image.get_pixbuf();
-                let disks = self.disks.borrow();
-                if let Some(image) = disks.get(&coord) {
-                    image.set_from_pixbuf(pixbuf.as_ref());
                 }
             }
+            GameStatus::Continue(_) => self.game_over_notified = false,
+            // The window has no way to resign or agree a draw.
+            GameStatus::Resigned(_) | GameStatus::DrawAgreed => (),
         }
-
-        self.buffer.clear();
-        self.buffer += format!("Black={}, White={}\n", black, white).as_str();
-        self.buffer += &self.engine.prompt;
-        self.status_line.set_text(&self.buffer);
     }
 }
 
@@ -135,13 +336,30 @@ pub struct Images {
 impl Images {
     fn new() -> Images {
         Images {
-            empty: Image::from_file("images/empty.png"),
-            black: Image::from_file("images/black.png"),
-            white: Image::from_file("images/white.png"),
+            empty: load_image("images/empty.png", EMPTY_PNG),
+            black: load_image("images/black.png", BLACK_PNG),
+            white: load_image("images/white.png", WHITE_PNG),
         }
     }
 }
 
+/// Prefers the image file on disk so it can be customized, and falls
+/// back to the copy embedded in the binary when the file is absent.
+fn load_image(path: &str, embedded: &'static [u8]) -> Image {
+    if Path::new(path).exists() {
+        return Image::from_file(path);
+    }
+
+    let pixbuf = decode_png(embedded).ok();
+    Image::from_pixbuf(pixbuf.as_ref())
+}
+
+fn decode_png(data: &'static [u8]) -> Result<Pixbuf, glib::Error> {
+    let bytes = glib::Bytes::from_static(data);
+    let stream = gio::MemoryInputStream::from_bytes(&bytes);
+    Pixbuf::from_stream(&stream, None::<&gio::Cancellable>)
+}
+
 struct UiParts {
     disks: Rc<RefCell<HashMap<Coordinate, Image>>>,
     init_button: Button,
@@ -150,10 +368,16 @@ struct UiParts {
     text: Rc<TextBuffer>,
 }
 
-fn create_application_window(app: &Application) -> ApplicationWindow {
+fn create_application_window(
+    app: &Application,
+    config: WindowConfig,
+) -> ApplicationWindow {
     let window = ApplicationWindow::new(app);
     window.set_title("Othello");
-    window.set_default_size(400, 300);
+    window.set_default_size(config.width, config.height);
+    if config.x.is_some() || config.y.is_some() {
+        window.move_(config.x.unwrap_or(0), config.y.unwrap_or(0));
+    }
 
     window
 }
@@ -169,12 +393,12 @@ fn build_ui(window: &ApplicationWindow, img_empty: &Image) -> UiParts {
 
     let pixbuf = img_empty.get_pixbuf();
     let mut disks = HashMap::with_capacity(64);
-    for col in 'a'..='h' {
-        for row in 1..=8 {
-            let coord = Coordinate::new(col, row);
+    for col in Column::iter() {
+        for row in Row::iter() {
+            let coord = Coordinate::from_parts(col, row);
             let image = Image::from_pixbuf(pixbuf.as_ref());
-            let x_pos = (col as u8 - b'a') as i32 * 32;
-            let y_pos = (row - 1) as i32 * 32;
+            let x_pos = col.to_u8() as i32 * 32;
+            let y_pos = row.to_u8() as i32 * 32;
             frame.put(&image, x_pos, y_pos);
             disks.insert(coord, image);
         }
@@ -198,3 +422,115 @@ fn build_ui(window: &ApplicationWindow, img_empty: &Image) -> UiParts {
         text: Rc::new(text_buf),
     }
 }
+
+// =====================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::GameEvents;
+    use super::WindowConfig;
+    use super::{changed_cells, decode_png, square_at, status_text};
+    use super::{BLACK_PNG, EMPTY_PNG, WHITE_PNG};
+    use crate::board::Board;
+    use crate::engine::{Command, Engine, Side};
+    use crate::position::Coordinate;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn embedded_images_decode() {
+        for data in &[EMPTY_PNG, BLACK_PNG, WHITE_PNG] {
+            let pixbuf = decode_png(data).unwrap();
+            assert_eq!(pixbuf.get_width(), 33);
+            assert_eq!(pixbuf.get_height(), 33);
+        }
+    }
+
+    #[test]
+    fn events_fire_once() {
+        let over = Rc::new(RefCell::new(Vec::new()));
+        let passes = Rc::new(RefCell::new(Vec::new()));
+        let mut events = GameEvents::default();
+        let over_clone = Rc::clone(&over);
+        events.on_game_over = Some(Box::new(move |black, white| {
+            over_clone.borrow_mut().push((black, white))
+        }));
+        let passes_clone = Rc::clone(&passes);
+        events.on_pass =
+            Some(Box::new(move |side| passes_clone.borrow_mut().push(side)));
+
+        let board: Board = "\
+xo.....x .......x .......x .......x .......x .......x .......o ........"
+            .parse()
+            .unwrap();
+        let mut engine = Engine::from_board(board, Side::Dark);
+        engine.action(Command::Init);
+        events.update(&engine);
+        engine.action(Command::Move(Coordinate::new('c', 1)));
+        events.update(&engine);
+        events.update(&engine);
+        assert_eq!(*passes.borrow(), vec![Side::Light]);
+        assert!(over.borrow().is_empty());
+
+        engine.action(Command::Move(Coordinate::new('h', 8)));
+        events.update(&engine);
+        engine.action(Command::Move(Coordinate::new('a', 8)));
+        events.update(&engine);
+        assert_eq!(*over.borrow(), vec![(11, 0)]);
+
+        let mut engine = Engine::new();
+        engine.action(Command::Init);
+        let mut events = GameEvents::default();
+        let over_clone = Rc::clone(&over);
+        events.on_game_over = Some(Box::new(move |black, white| {
+            over_clone.borrow_mut().push((black, white))
+        }));
+        while let Some(coord) = engine.hint() {
+            engine.action(Command::Move(coord));
+            events.update(&engine);
+        }
+        events.update(&engine);
+        assert_eq!(over.borrow().len(), 2);
+    }
+
+    #[test]
+    fn status_text_counts_disks() {
+        let mut engine = Engine::new();
+        engine.action(Command::Init);
+        assert_eq!(
+            status_text(&engine),
+            "Black=2, White=2\nGame start! Black's turn."
+        );
+        engine.action(Command::Move(Coordinate::new('f', 5)));
+        assert_eq!(status_text(&engine), "Black=4, White=1\nWhite's turn.");
+    }
+
+    #[test]
+    fn square_at_click() {
+        assert_eq!(square_at(0.0, 0.0), Some(Coordinate::new('a', 1)));
+        assert_eq!(square_at(100.0, 40.0), Some(Coordinate::new('d', 2)));
+        assert_eq!(square_at(255.0, 255.0), Some(Coordinate::new('h', 8)));
+        assert_eq!(square_at(256.0, 10.0), None);
+        assert_eq!(square_at(10.0, 256.0), None);
+    }
+
+    #[test]
+    fn window_config_default() {
+        let config = WindowConfig::default();
+        assert_eq!((config.width, config.height), (400, 300));
+        assert_eq!((config.x, config.y), (None, None));
+    }
+
+    #[test]
+    fn changed_cells_after_move() {
+        let mut engine = Engine::new();
+        engine.action(Command::Init);
+        let before = engine.snapshot();
+        assert_eq!(changed_cells(None, &before).len(), 64);
+        assert!(changed_cells(Some(&before), &before).is_empty());
+
+        engine.action(Command::Move(Coordinate::new('f', 5)));
+        let after = engine.snapshot();
+        assert_eq!(changed_cells(Some(&before), &after), vec![36, 37]);
+    }
+}
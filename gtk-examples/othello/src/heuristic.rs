@@ -0,0 +1,155 @@
+use crate::board::{flip_disk, Board, Disk};
+use crate::engine::Side;
+
+pub const STANDARD_WEIGHTS: [[i32; 8]; 8] = [
+    [100, -10, 10, 5, 5, 10, -10, 100],
+    [-10, -25, -2, -2, -2, -2, -25, -10],
+    [10, -2, 1, 1, 1, 1, -2, 10],
+    [5, -2, 1, 0, 0, 1, -2, 5],
+    [5, -2, 1, 0, 0, 1, -2, 5],
+    [10, -2, 1, 1, 1, 1, -2, 10],
+    [-10, -25, -2, -2, -2, -2, -25, -10],
+    [100, -10, 10, 5, 5, 10, -10, 100],
+];
+
+/// The opening lasts while at least this many squares are empty.
+pub const OPENING_MIN_EMPTIES: usize = 44;
+/// The endgame starts once at most this many squares are empty.
+pub const ENDGAME_MAX_EMPTIES: usize = 16;
+
+/// How much `composite_score` counts each term in a phase: square
+/// weights and potential mobility early on, both with disks in the
+/// midgame, and disks and parity at the end.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PhaseWeights {
+    pub position: i32,
+    pub potential_mobility: i32,
+    pub material: i32,
+    /// Bonus for the side to move when the number of empty squares is
+    /// odd, so that it tends to get the last move.
+    pub parity: i32,
+}
+
+pub const OPENING_WEIGHTS: PhaseWeights = PhaseWeights {
+    position: 1,
+    potential_mobility: 3,
+    material: 0,
+    parity: 0,
+};
+pub const MIDGAME_WEIGHTS: PhaseWeights = PhaseWeights {
+    position: 1,
+    potential_mobility: 2,
+    material: 1,
+    parity: 0,
+};
+pub const ENDGAME_WEIGHTS: PhaseWeights = PhaseWeights {
+    position: 0,
+    potential_mobility: 0,
+    material: 1,
+    parity: 2,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GamePhase {
+    Opening,
+    Midgame,
+    Endgame,
+}
+
+/// Whether the number of empty squares is even or odd. Without passes,
+/// the side to move gets the last move when it is odd.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Parity {
+    Even,
+    Odd,
+}
+
+impl GamePhase {
+    pub fn weights(self) -> PhaseWeights {
+        match self {
+            GamePhase::Opening => OPENING_WEIGHTS,
+            GamePhase::Midgame => MIDGAME_WEIGHTS,
+            GamePhase::Endgame => ENDGAME_WEIGHTS,
+        }
+    }
+}
+
+/// Scores the board for `disk` to move by blending the square weight,
+/// potential mobility and disk differences and the parity with the
+/// weights of the board's phase.
+pub fn composite_score(board: &Board, disk: Disk) -> i32 {
+    let opponent = flip_disk(&disk);
+    let (side, other) = match disk {
+        Disk::Black => (Side::Dark, Side::Light),
+        Disk::White => (Side::Light, Side::Dark),
+    };
+    let weights = board.phase().weights();
+    let position = board.weighted_disk_score(&STANDARD_WEIGHTS, disk);
+    // The costliest term, left out of the phases that don't weigh it.
+    let potential_mobility = match weights.potential_mobility {
+        0 => 0,
+        _ => {
+            board.potential_mobility(side) as i32
+                - board.potential_mobility(other) as i32
+        }
+    };
+    let material = board.count(disk) as i32 - board.count(opponent) as i32;
+    let parity = match board.parity() {
+        Parity::Odd => 1,
+        Parity::Even => 0,
+    };
+
+    weights.position * position
+        + weights.potential_mobility * potential_mobility
+        + weights.material * material
+        + weights.parity * parity
+}
+
+// =====================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{composite_score, ENDGAME_WEIGHTS, STANDARD_WEIGHTS};
+    use crate::board::{Board, Disk};
+
+    #[test]
+    fn standard_weights_are_symmetric() {
+        for row in 0..8 {
+            for col in 0..8 {
+                let weight = STANDARD_WEIGHTS[row][col];
+                assert_eq!(weight, STANDARD_WEIGHTS[col][row]);
+                assert_eq!(weight, STANDARD_WEIGHTS[7 - row][col]);
+                assert_eq!(weight, STANDARD_WEIGHTS[row][7 - col]);
+            }
+        }
+    }
+
+    #[test]
+    fn composite_score_by_phase() {
+        // What holding a1 rather than the opponent is worth, everything
+        // else being equal.
+        let corner = |board: &str| {
+            let theirs: Board = format!("o{}", &board[1..]).parse().unwrap();
+            let ours: Board = format!("x{}", &board[1..]).parse().unwrap();
+            composite_score(&ours, Disk::Black)
+                - composite_score(&theirs, Disk::Black)
+        };
+
+        let opening = corner(
+            "\
+........ ........ ........ ...ox... ...xo... ........ ........ ........",
+        );
+        let endgame = corner(
+            "\
+.ooooooo xxxxxxxx xxxxxxxx oooooooo oooooooo xxxxxxxx oooooooo ........",
+        );
+        let midgame = corner(
+            "\
+.xxxxxxx xxxxxxxx ........ ........ ........ oooooooo oooooooo ........",
+        );
+        assert_eq!(endgame, 2 * ENDGAME_WEIGHTS.material);
+        assert!(opening > 100, "opening corner worth {}", opening);
+        assert!(midgame > 100, "midgame corner worth {}", midgame);
+        assert!(opening > endgame && midgame > endgame);
+    }
+}
@@ -0,0 +1,81 @@
+use crate::board::Board;
+use crate::position::Coordinate;
+use std::cell::{Ref, RefCell};
+
+/// Every ply of the game in order, with the board after it. A pass is
+/// recorded with `None`.
+#[derive(Default)]
+pub struct GameHistory {
+    moves: RefCell<Vec<(Option<Coordinate>, Board)>>,
+}
+
+impl GameHistory {
+    pub fn new() -> GameHistory {
+        GameHistory {
+            moves: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub fn record(&self, coord: Option<Coordinate>, board: Board) {
+        self.moves.borrow_mut().push((coord, board));
+    }
+
+    pub fn truncate(&self, len: usize) {
+        self.moves.borrow_mut().truncate(len);
+    }
+
+    pub fn len(&self) -> usize {
+        self.moves.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moves.borrow().is_empty()
+    }
+
+    pub fn nth_move(&self, n: usize) -> Option<Option<Coordinate>> {
+        self.moves.borrow().get(n).map(|(coord, _)| *coord)
+    }
+
+    pub fn nth_board(&self, n: usize) -> Option<Ref<'_, Board>> {
+        if n < self.len() {
+            Some(Ref::map(self.moves.borrow(), |moves| &moves[n].1))
+        } else {
+            None
+        }
+    }
+}
+
+// =====================================================================
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{Board, Disk};
+    use crate::engine::{Command, Engine};
+    use crate::position::Coordinate;
+
+    #[test]
+    fn history_records_each_move() {
+        let (mut engine, history) = Engine::with_history();
+        engine.action(Command::Init);
+        assert!(history.is_empty());
+
+        let moves = [('f', 5), ('d', 6), ('c', 3), ('d', 3), ('c', 4)];
+        for &(col, row) in moves.iter() {
+            engine.action(Command::Move(Coordinate::new(col, row)));
+        }
+        assert_eq!(history.len(), 5);
+        assert_eq!(history.nth_move(4), Some(Some(Coordinate::new('c', 4))));
+        assert!(history.nth_board(5).is_none());
+
+        let mut board = Board::new();
+        board.init();
+        let first = board.try_move(Coordinate::new('f', 5), Disk::Black);
+        assert_eq!(*history.nth_board(0).unwrap(), first.unwrap());
+        assert_eq!(*history.nth_board(4).unwrap(), *engine.current_board());
+
+        engine.action(Command::Undo);
+        assert_eq!(history.len(), 4);
+        engine.action(Command::Init);
+        assert!(history.is_empty());
+    }
+}
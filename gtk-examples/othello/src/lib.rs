@@ -1,6 +1,17 @@
+pub mod ai;
+pub mod ansi;
+pub mod board;
+pub mod book;
 pub mod cui;
+pub mod engine;
 pub mod gui;
-
-mod board;
-mod engine;
-mod position;
+pub mod heuristic;
+pub mod history;
+pub mod mcts;
+pub mod messages;
+pub mod position;
+pub mod render;
+pub mod replay;
+pub mod search;
+pub mod strategy;
+pub mod timing;
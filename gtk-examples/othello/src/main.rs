@@ -1,26 +1,377 @@
-use clap::{App, Arg};
-use othello::{cui, gui};
+use clap::{App, Arg, ArgMatches};
+use othello::ansi;
+use othello::board::Board;
+use othello::book::OpeningBook;
+use othello::cui::{self, RenderConfig};
+use othello::engine::{Engine, Side};
+use othello::gui::{self, WindowConfig};
+use othello::strategy::{self, HumanStrategy, Strategy};
+use std::path::Path;
 use std::process;
+use std::sync::Arc;
+use std::time::Duration;
 
-fn main() {
-    let matches = App::new("Othello")
+const AI_DEPTH: u8 = 3;
+
+fn app() -> App<'static, 'static> {
+    App::new("Othello")
         .arg(
             Arg::with_name("graph")
                 .short("g")
                 .long("graph")
                 .help("Use graphical user interface."),
         )
-        .get_matches();
+        .arg(
+            Arg::with_name("symbols")
+                .long("symbols")
+                .takes_value(true)
+                .value_name("BWE")
+                .help("Characters for black, white and empty squares."),
+        )
+        .arg(
+            Arg::with_name("plain")
+                .long("plain")
+                .help("Draw the board without colors."),
+        )
+        .arg(
+            Arg::with_name("output")
+                .long("output")
+                .takes_value(true)
+                .possible_values(&["text", "json"])
+                .default_value("text")
+                .help("Print positions as text or as one JSON object a line."),
+        )
+        .arg(
+            Arg::with_name("position")
+                .long("position")
+                .takes_value(true)
+                .value_name("CODE")
+                .help(
+                    "Start from a board given as 64 characters of x, o and .",
+                ),
+        )
+        .arg(
+            Arg::with_name("turn")
+                .long("turn")
+                .takes_value(true)
+                .possible_values(&["black", "white"])
+                .default_value("black")
+                .help("Side to move first."),
+        )
+        .arg(
+            Arg::with_name("watch")
+                .long("watch")
+                .takes_value(true)
+                .min_values(0)
+                .value_name("MS")
+                .help(
+                    "Let the computer play itself, pausing MS between moves.",
+                ),
+        )
+        .arg(
+            Arg::with_name("ai")
+                .long("ai")
+                .help("Play against the computer."),
+        )
+        .arg(
+            Arg::with_name("ai-side")
+                .long("ai-side")
+                .takes_value(true)
+                .possible_values(&["black", "white"])
+                .default_value("white")
+                .help("Side played by the computer with --ai."),
+        )
+        .arg(
+            Arg::with_name("book")
+                .long("book")
+                .takes_value(true)
+                .value_name("FILE")
+                .conflicts_with_all(&["script", "selfplay", "watch"])
+                .help("Opening book of transcripts for the computer players."),
+        )
+        .arg(
+            Arg::with_name("black-name")
+                .long("black-name")
+                .takes_value(true)
+                .value_name("NAME")
+                .help("Name of the black player."),
+        )
+        .arg(
+            Arg::with_name("white-name")
+                .long("white-name")
+                .takes_value(true)
+                .value_name("NAME")
+                .help("Name of the white player."),
+        )
+        .arg(
+            Arg::with_name("script")
+                .long("script")
+                .takes_value(true)
+                .value_name("FILE")
+                .help("Run the commands in FILE and print the output."),
+        )
+        .arg(
+            Arg::with_name("selfplay")
+                .long("selfplay")
+                .takes_value(true)
+                .min_values(1)
+                .max_values(4)
+                .value_names(&["GAMES", "BLACK", "WHITE", "SEED"])
+                .help("Play GAMES computer games and print a summary."),
+        )
+        .arg(
+            Arg::with_name("verbose")
+                .long("verbose")
+                .help("Show each final board with --selfplay."),
+        )
+        .arg(
+            Arg::with_name("seed")
+                .long("seed")
+                .takes_value(true)
+                .value_name("N")
+                .help("Seed the random players to replay their moves."),
+        )
+        .arg(pixels("width", "Width of the window."))
+        .arg(pixels("height", "Height of the window."))
+        .arg(pixels("x", "Horizontal position of the window."))
+        .arg(pixels("y", "Vertical position of the window."))
+}
+
+fn pixels(name: &'static str, help: &'static str) -> Arg<'static, 'static> {
+    Arg::with_name(name)
+        .long(name)
+        .takes_value(true)
+        .value_name("PX")
+        .help(help)
+}
+
+/// Returns the black and white players, the computer taking the side
+/// given by `--ai-side` when `--ai` is set, and playing from `book` if
+/// one was loaded.
+fn players(
+    matches: &ArgMatches,
+    book: Option<&Arc<OpeningBook>>,
+) -> (Box<dyn Strategy>, Box<dyn Strategy>) {
+    let human = || Box::new(HumanStrategy) as Box<dyn Strategy>;
+    if !matches.is_present("ai") {
+        return (human(), human());
+    }
 
-    if matches.is_present("graph") {
-        if let Err(err) = gui::run() {
+    let ai = strategy::minimax(AI_DEPTH, book);
+    match matches.value_of("ai-side") {
+        Some("black") => (ai, human()),
+        _ => (human(), ai),
+    }
+}
+
+/// Reads the window flags, keeping the defaults for the missing ones.
+fn window_config(matches: &ArgMatches) -> Result<WindowConfig, String> {
+    let pixels = |name| match matches.value_of(name) {
+        Some(value) => match value.parse() {
+            Ok(px) => Ok(Some(px)),
+            Err(_) => Err(format!("Invalid --{}: {}", name, value)),
+        },
+        None => Ok(None),
+    };
+
+    let default = WindowConfig::default();
+    Ok(WindowConfig {
+        width: pixels("width")?.unwrap_or(default.width),
+        height: pixels("height")?.unwrap_or(default.height),
+        x: pixels("x")?,
+        y: pixels("y")?,
+    })
+}
+
+/// Reads `--seed`, which is `None` when the random players should be
+/// seeded from entropy.
+fn seed(matches: &ArgMatches) -> Result<Option<u64>, String> {
+    match matches.value_of("seed") {
+        Some(value) => match value.parse() {
+            Ok(seed) => Ok(Some(seed)),
+            Err(_) => Err(format!("Invalid --seed: {}", value)),
+        },
+        None => Ok(None),
+    }
+}
+
+/// Reads the book given by `--book`, if any.
+fn book(matches: &ArgMatches) -> Result<Option<Arc<OpeningBook>>, String> {
+    match matches.value_of("book") {
+        Some(path) => match OpeningBook::from_file(Path::new(path)) {
+            Ok(book) => Ok(Some(Arc::new(book))),
+            Err(err) => Err(format!("Invalid book {}: {}", path, err)),
+        },
+        None => Ok(None),
+    }
+}
+
+fn main() {
+    let matches = app().get_matches();
+    let book = match book(&matches) {
+        Ok(book) => book,
+        Err(err) => {
+            eprintln!("{}", err);
+            process::exit(1);
+        }
+    };
+
+    if let Some(path) = matches.value_of("script") {
+        match cui::run_script(Path::new(path)) {
+            Ok(output) => print!("{}", output),
+            Err(err) => {
+                eprintln!("Script error in {}: {}", path, err);
+                process::exit(1);
+            }
+        }
+    } else if matches.is_present("graph") {
+        let config = match window_config(&matches) {
+            Ok(config) => config,
+            Err(err) => {
+                eprintln!("{}", err);
+                process::exit(1);
+            }
+        };
+        let computer = match matches.value_of("ai-side") {
+            _ if !matches.is_present("ai") => None,
+            Some("black") => Some((Side::Dark, AI_DEPTH)),
+            _ => Some((Side::Light, AI_DEPTH)),
+        };
+        if book.is_some() && computer.is_none() {
+            eprintln!("--book needs --ai with --graph");
+            process::exit(1);
+        }
+        if let Err(err) = gui::run(config, computer, book) {
             eprintln!("Application error: {}", err);
             process::exit(1);
         }
     } else {
-        if let Err(err) = cui::run() {
+        let mut config = match matches.value_of("symbols") {
+            Some(symbols) => match RenderConfig::parse(symbols) {
+                Some(config) => config,
+                None => {
+                    eprintln!("Invalid symbols: {}", symbols);
+                    process::exit(1);
+                }
+            },
+            None => RenderConfig::default(),
+        };
+        let seed = match seed(&matches) {
+            Ok(seed) => seed,
+            Err(err) => {
+                eprintln!("{}", err);
+                process::exit(1);
+            }
+        };
+        config.json = matches.value_of("output") == Some("json");
+        config.color =
+            !matches.is_present("plain") && !config.json && ansi::enabled();
+        let turn = match matches.value_of("turn") {
+            Some("white") => Side::Light,
+            _ => Side::Dark,
+        };
+        let mut engine = match matches.value_of("position") {
+            Some(code) => match Board::from_compact_string(code) {
+                Ok(board) => Engine::from_board(board, turn),
+                Err(err) => {
+                    eprintln!("Invalid position {}: {:?}", code, err);
+                    process::exit(1);
+                }
+            },
+            None => Engine::with_turn(turn),
+        };
+        if let Some(name) = matches.value_of("black-name") {
+            engine.set_name(Side::Dark, name);
+        }
+        if let Some(name) = matches.value_of("white-name") {
+            engine.set_name(Side::Light, name);
+        }
+        if let Some(args) = matches.values_of("selfplay") {
+            let mut args: Vec<_> = args.collect();
+            if matches.is_present("verbose") {
+                args.push("--verbose");
+            }
+            if let Err(err) = cui::selfplay(config, &args, seed) {
+                eprintln!("Application error: {}", err);
+                process::exit(1);
+            }
+            return;
+        }
+        if matches.is_present("watch") {
+            let ms = matches.value_of("watch").unwrap_or("500");
+            let delay = match ms.parse() {
+                Ok(ms) => Duration::from_millis(ms),
+                Err(_) => {
+                    eprintln!("Invalid delay: {}", ms);
+                    process::exit(1);
+                }
+            };
+            if let Err(err) = cui::watch(engine, config, delay) {
+                eprintln!("Application error: {}", err);
+                process::exit(1);
+            }
+            return;
+        }
+        let (black, white) = players(&matches, book.as_ref());
+        if let Err(err) = cui::run(engine, config, black, white, seed, book) {
             eprintln!("Application error: {}", err);
             process::exit(1);
         }
     }
 }
+
+// =====================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{app, players, seed, window_config};
+    use othello::book::OpeningBook;
+    use othello::gui::WindowConfig;
+    use std::sync::Arc;
+
+    #[test]
+    fn parse_window_flags() {
+        let matches = app().get_matches_from(vec!["othello", "-g"]);
+        assert_eq!(window_config(&matches), Ok(WindowConfig::default()));
+
+        let args = vec!["othello", "-g", "--width", "640", "--x", "10"];
+        let matches = app().get_matches_from(args);
+        let config = window_config(&matches).unwrap();
+        assert_eq!((config.width, config.height), (640, 300));
+        assert_eq!((config.x, config.y), (Some(10), None));
+
+        let args = vec!["othello", "--height", "tall"];
+        let matches = app().get_matches_from(args);
+        assert!(window_config(&matches).is_err());
+    }
+
+    #[test]
+    fn parse_seed_flag() {
+        let matches = app().get_matches_from(vec!["othello"]);
+        assert_eq!(seed(&matches), Ok(None));
+        let args = vec!["othello", "--seed", "42"];
+        assert_eq!(seed(&app().get_matches_from(args)), Ok(Some(42)));
+        let args = vec!["othello", "--seed", "lucky"];
+        assert!(seed(&app().get_matches_from(args)).is_err());
+    }
+
+    #[test]
+    fn parse_ai_flags() {
+        let matches = app().get_matches_from(vec!["othello"]);
+        let (black, white) = players(&matches, None);
+        assert!(black.is_human() && white.is_human());
+
+        let matches = app().get_matches_from(vec!["othello", "--ai"]);
+        let (black, white) = players(&matches, None);
+        assert!(black.is_human() && !white.is_human());
+
+        let args = vec!["othello", "--ai", "--ai-side", "black"];
+        let book = OpeningBook::load("f5d6").ok().map(Arc::new);
+        let matches = app().get_matches_from(args);
+        let (black, white) = players(&matches, book.as_ref());
+        assert!(!black.is_human() && white.is_human());
+
+        let args = vec!["othello", "--watch", "--book", "book.txt"];
+        assert!(app().get_matches_from_safe(args).is_err());
+    }
+}
@@ -0,0 +1,228 @@
+use crate::board::{Board, Disk};
+use crate::engine::{change_turn, Side};
+use crate::position::Coordinate;
+use crate::strategy::{RandomStrategy, Strategy};
+use std::cmp::Ordering;
+
+const EXPLORATION: f64 = std::f64::consts::SQRT_2;
+
+/// Picks moves by Monte Carlo tree search, playing `iterations` random
+/// games to the end.
+pub struct MctsPlayer {
+    pub iterations: u32,
+}
+
+impl MctsPlayer {
+    pub fn new(iterations: u32) -> MctsPlayer {
+        MctsPlayer { iterations }
+    }
+
+    /// Returns the most visited move after the search, or `None` when
+    /// `side` has to pass.
+    pub fn best_move(&self, board: &Board, side: Side) -> Option<Coordinate> {
+        if board.valid_moves(side.to_disk()).is_empty() {
+            return None;
+        }
+
+        let root = self.search(board, side);
+        root.children
+            .iter()
+            .max_by(|a, b| match a.visits.cmp(&b.visits) {
+                // Keep the first of equally visited moves.
+                Ordering::Equal => Ordering::Greater,
+                ord => ord,
+            })
+            .and_then(|child| child.coord)
+    }
+
+    /// Builds the search tree from `board` with `side` to move.
+    pub fn search(&self, board: &Board, side: Side) -> MctsNode {
+        let mut random = RandomStrategy::new();
+        let mut root = MctsNode::new(board.clone(), side, None);
+        root.expand();
+        for _ in 0..self.iterations {
+            root.iterate(&mut random);
+        }
+
+        root
+    }
+}
+
+// ---------------------------------------------------------------------
+
+/// A position in the search tree. `wins` counts for the side that moved
+/// into it, a draw as half a win.
+pub struct MctsNode {
+    pub board: Board,
+    pub side: Side,
+    pub coord: Option<Coordinate>,
+    pub visits: u32,
+    pub wins: f64,
+    pub children: Vec<MctsNode>,
+}
+
+impl MctsNode {
+    fn new(board: Board, side: Side, coord: Option<Coordinate>) -> MctsNode {
+        MctsNode {
+            board,
+            side,
+            coord,
+            visits: 0,
+            wins: 0.0,
+            children: Vec::new(),
+        }
+    }
+
+    /// Adds a child for each move of the side to move, or a single pass
+    /// when it has none. A finished game gets no children.
+    fn expand(&mut self) {
+        let disk = self.side.to_disk();
+        let moves = self.board.valid_moves(disk);
+        let next = change_turn(self.side);
+        if moves.is_empty() {
+            if !self.board.valid_moves(next.to_disk()).is_empty() {
+                let child = MctsNode::new(self.board.clone(), next, None);
+                self.children.push(child);
+            }
+            return;
+        }
+
+        let successors = self.board.generate_all_successors(disk);
+        for (coord, board) in moves.into_iter().zip(successors) {
+            self.children.push(MctsNode::new(board, next, Some(coord)));
+        }
+    }
+
+    /// Runs one selection, expansion, rollout and backpropagation below
+    /// this node, returning the winner of the rollout.
+    fn iterate(&mut self, random: &mut RandomStrategy) -> Option<Side> {
+        let winner = if self.visits == 0 && self.children.is_empty() {
+            rollout(&self.board, self.side, random)
+        } else {
+            if self.children.is_empty() {
+                self.expand();
+            }
+            match self.select() {
+                Some(child) => child.iterate(random),
+                None => winner(&self.board),
+            }
+        };
+
+        self.visits += 1;
+        match winner {
+            Some(side) if side != self.side => self.wins += 1.0,
+            None => self.wins += 0.5,
+            _ => {}
+        }
+
+        winner
+    }
+
+    /// Returns the child with the highest UCB1 score, trying unvisited
+    /// ones first.
+    fn select(&mut self) -> Option<&mut MctsNode> {
+        let log_visits = f64::from(self.visits.max(1)).ln();
+        let score = |node: &MctsNode| {
+            if node.visits == 0 {
+                return f64::INFINITY;
+            }
+            let visits = f64::from(node.visits);
+            node.wins / visits + EXPLORATION * (log_visits / visits).sqrt()
+        };
+
+        let mut best: Option<(usize, f64)> = None;
+        for (index, child) in self.children.iter().enumerate() {
+            let value = score(child);
+            match best {
+                Some((_, best)) if value <= best => {}
+                _ => best = Some((index, value)),
+            }
+        }
+
+        best.map(move |(index, _)| &mut self.children[index])
+    }
+}
+
+/// Plays random moves from `board` until neither side can move.
+fn rollout(
+    board: &Board,
+    side: Side,
+    random: &mut RandomStrategy,
+) -> Option<Side> {
+    let mut board = board.clone();
+    let mut side = side;
+    let mut passed = false;
+    loop {
+        let moves = board.valid_moves(side.to_disk());
+        match random.choose_move(&board, side, &moves) {
+            Some(coord) => {
+                board = board
+                    .try_move(coord, side.to_disk())
+                    .expect("illegal move");
+                passed = false;
+            }
+            None if passed => break,
+            None => passed = true,
+        }
+        side = change_turn(side);
+    }
+
+    winner(&board)
+}
+
+fn winner(board: &Board) -> Option<Side> {
+    match board.count(Disk::Black).cmp(&board.count(Disk::White)) {
+        Ordering::Greater => Some(Side::Dark),
+        Ordering::Less => Some(Side::Light),
+        Ordering::Equal => None,
+    }
+}
+
+// =====================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{MctsNode, MctsPlayer};
+    use crate::board::{Board, Disk};
+    use crate::engine::Side;
+
+    #[test]
+    fn mcts_returns_legal_move() {
+        let mut board = Board::new();
+        board.init();
+        let player = MctsPlayer::new(200);
+        let coord = player.best_move(&board, Side::Dark).unwrap();
+        assert!(board.valid_moves(Disk::Black).contains(&coord));
+
+        let board: Board = "\
+xo.....x .......x .......x .......x .......x .......x .......o ........"
+            .parse()
+            .unwrap();
+        assert_eq!(player.best_move(&board, Side::Light), None);
+        let coord = player.best_move(&board, Side::Dark);
+        assert!(board.valid_moves(Disk::Black).contains(&coord.unwrap()));
+    }
+
+    #[test]
+    fn mcts_visits_add_up() {
+        fn check(node: &MctsNode) {
+            if node.children.is_empty() {
+                return;
+            }
+            let sum: u32 = node.children.iter().map(|c| c.visits).sum();
+            // A node is rolled out once before its children are added.
+            assert!(sum == node.visits || sum + 1 == node.visits);
+            assert!(node.wins <= f64::from(node.visits));
+            node.children.iter().for_each(check);
+        }
+
+        let mut board = Board::new();
+        board.init();
+        let root = MctsPlayer::new(300).search(&board, Side::Dark);
+        assert_eq!(root.visits, 300);
+        assert_eq!(root.children.len(), 4);
+        let sum: u32 = root.children.iter().map(|c| c.visits).sum();
+        assert_eq!(sum, 300);
+        check(&root);
+    }
+}
@@ -0,0 +1,377 @@
+use crate::board::MoveErr;
+use crate::engine::{change_turn, GameStatus, Side};
+use crate::position::Coordinate;
+use std::cmp::Ordering;
+use std::env;
+use std::fmt;
+
+const HELP: &str = "\n\
+Command:
+  quit => Quit the program, asking first if a game is in progress;
+      `quit!` does not ask.
+  help => Show this help message.
+  show => Print the board and status again, also as `board`.
+  init => Start a new game, asking like `quit`; `init!` does not ask.
+  resign => Give up the game for the side to move.
+  draw => Offer a draw, which the other side accepts by answering `draw`
+      on its turn before it moves.
+  undo [count] => Go back to previous move, or take back that many
+      moves at once. Undo also takes back a resignation or a draw.
+  redo [count] => Replay moves taken back by undo, one by default.
+  symbols {black} {white} {empty} => Change the characters used to draw
+      the board, such as `symbols # O .`.
+  move {coordinate} => Press disk at the position with coordinate,
+      such as `move a1`, `move c4` or `move h8`.
+  hint => Show the legal moves and a suggested one.
+  perft {depth} => Count the positions reached from this one at each
+      depth up to `depth`, 9 at most, timing each count.
+  eval [depth] => Score each legal move by searching `depth` plies, 3 by
+      default and 5 at most, with the line expected after it.
+  times => Show how long each move took and the total for each side.
+  score => Show the disk counts and how black's lead changed each ply.
+  hints on|off => Mark the squares where the side to move may play.
+  history => List the moves played so far, also as `moves`. A `|`
+      marks the current position after an undo, and `--` a pass.
+  export [art] => Print the board along with its 64-character code and
+      the side to move, or with `art` drawn in box-drawing characters
+      for pasting into documents.
+  save [file] => Save the moves played so far, by default to
+      `othello-autosave.txt`.
+  load [file] => Replay the moves saved in a file.
+  ai [black|white] {player} => Let the computer play a side, by default
+      the one not to move, where the player is one of `off`,
+      `random [seed]`, `greedy` or `minimax {depth}`.
+  name black|white {name} => Call a player by name in the prompt and
+      the status, such as `name black Alice`.
+  lang en|ja => Show the status in English or Japanese. The language
+      is taken from `LANG` at startup.
+  selfplay {games} [black] [white] [seed] [--verbose] => Let two
+      computer players, `random` by default, play whole games and sum up
+      the results. The same seed gives the same games, and `--verbose`
+      shows each final board.
+  vs {black} {white} => Choose the players, each of `human`, `random` or
+      `ai:{depth}`, such as `vs human ai:4`.
+
+Aliases: m => move, u => undo, r => redo, q => quit, h => help,
+  n => init.
+";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Lang {
+    English,
+    Japanese,
+    Custom(&'static Catalog),
+}
+
+/// A language defined outside this module, which `translate` renders
+/// from the English text of every message, status and prompt.
+pub struct Catalog {
+    pub code: &'static str,
+    pub translate: fn(&str) -> String,
+}
+
+impl PartialEq for Catalog {
+    fn eq(&self, other: &Catalog) -> bool {
+        self.code == other.code
+    }
+}
+
+impl fmt::Debug for Catalog {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Catalog({})", self.code)
+    }
+}
+
+/// Something the engine tells the player along with the status.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GameMessage {
+    GameStart,
+    CantPlace(Coordinate, MoveErr),
+    CantPass,
+    Undo,
+    CantUndo,
+    Redo,
+    NothingToRedo,
+    DrawOffered,
+    NoDrawOffer,
+    /// The number of plies replayed from a saved game.
+    Loaded(usize),
+    Thinking,
+}
+
+impl Lang {
+    /// Picks Japanese when `LC_ALL`, `LC_MESSAGES` or `LANG` names it,
+    /// checked in that order, and English otherwise.
+    pub fn from_env() -> Lang {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Lang::parse(&value))
+            .unwrap_or(Lang::English)
+    }
+
+    /// Parses a language such as `en`, `ja` or `ja_JP.UTF-8`.
+    pub fn parse(code: &str) -> Option<Lang> {
+        let code = code.to_lowercase();
+        if code.starts_with("ja") {
+            Some(Lang::Japanese)
+        } else if code.starts_with("en") || code == "c" || code == "posix" {
+            Some(Lang::English)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the default name of a player. A custom language keeps the
+    /// English one, which its `translate` sees inside the status text.
+    pub fn side_name(self, side: Side) -> &'static str {
+        match (self, side) {
+            (Lang::English, _) | (Lang::Custom(_), _) => side.label(),
+            (Lang::Japanese, Side::Dark) => "黒",
+            (Lang::Japanese, Side::Light) => "白",
+        }
+    }
+
+    pub fn message(self, message: GameMessage) -> String {
+        match self {
+            Lang::Custom(catalog) => {
+                (catalog.translate)(&Lang::English.message(message))
+            }
+            Lang::English => match message {
+                GameMessage::GameStart => "Game start!".to_string(),
+                GameMessage::CantPlace(coord, MoveErr::NotEmpty) => {
+                    format!("{} is already occupied.", coord)
+                }
+                GameMessage::CantPlace(coord, MoveErr::NoDiskFlipped) => {
+                    format!("{} would not flip any disks.", coord)
+                }
+                GameMessage::CantPlace(_, MoveErr::OutOfBounds) => {
+                    "Can't place there!".to_string()
+                }
+                GameMessage::CantPass => "Can't pass!".to_string(),
+                GameMessage::Undo => "Undo, and".to_string(),
+                GameMessage::CantUndo => "Can't undo!".to_string(),
+                GameMessage::Redo => "Redo, and".to_string(),
+                GameMessage::NothingToRedo => "Nothing to redo!".to_string(),
+                GameMessage::DrawOffered => "Draw offered.".to_string(),
+                GameMessage::NoDrawOffer => "No draw to accept!".to_string(),
+                GameMessage::Loaded(plies) => {
+                    format!("Loaded {} moves.", plies)
+                }
+                GameMessage::Thinking => "Thinking…".to_string(),
+            },
+            Lang::Japanese => match message {
+                GameMessage::GameStart => "ゲーム開始！".to_string(),
+                GameMessage::CantPlace(coord, MoveErr::NotEmpty) => {
+                    format!("{}にはもう石があります。", coord)
+                }
+                GameMessage::CantPlace(coord, MoveErr::NoDiskFlipped) => {
+                    format!("{}では石を返せません。", coord)
+                }
+                GameMessage::CantPlace(_, MoveErr::OutOfBounds) => {
+                    "そこには置けません！".to_string()
+                }
+                GameMessage::CantPass => "パスできません！".to_string(),
+                GameMessage::Undo => "一手戻して、".to_string(),
+                GameMessage::CantUndo => "戻せません！".to_string(),
+                GameMessage::Redo => "一手進めて、".to_string(),
+                GameMessage::NothingToRedo => {
+                    "進める手がありません！".to_string()
+                }
+                GameMessage::DrawOffered => {
+                    "引き分けを提案しました。".to_string()
+                }
+                GameMessage::NoDrawOffer => {
+                    "引き分けの提案がありません！".to_string()
+                }
+                GameMessage::Loaded(plies) => {
+                    format!("{}手を読み込みました。", plies)
+                }
+                GameMessage::Thinking => "考え中…".to_string(),
+            },
+        }
+    }
+
+    /// Describes the status, calling the players by the given names.
+    pub fn status(
+        self,
+        status: GameStatus,
+        black: &str,
+        white: &str,
+    ) -> String {
+        let name = |side| match side {
+            Side::Dark => black,
+            Side::Light => white,
+        };
+        match (self, status) {
+            (Lang::Custom(catalog), _) => (catalog.translate)(
+                &Lang::English.status(status, black, white),
+            ),
+            (Lang::English, GameStatus::Continue(side)) => {
+                format!("{}'s turn.", name(side))
+            }
+            (Lang::English, GameStatus::PassBack(side)) => format!(
+                "{} passed, and {}'s turn.",
+                name(side),
+                name(change_turn(side))
+            ),
+            (Lang::English, GameStatus::GameOver(b, w)) => match b.cmp(&w) {
+                Ordering::Greater => {
+                    format!("Game over: {} wins {}–{}.", black, b, w)
+                }
+                Ordering::Less => {
+                    format!("Game over: {} wins {}–{}.", white, w, b)
+                }
+                Ordering::Equal => format!("Game over: draw {}–{}.", b, w),
+            },
+            (Lang::English, GameStatus::Resigned(side)) => format!(
+                "Game over: {} resigned, and {} wins.",
+                name(side),
+                name(change_turn(side))
+            ),
+            (Lang::English, GameStatus::DrawAgreed) => {
+                "Game over: draw agreed.".to_string()
+            }
+            (Lang::Japanese, GameStatus::Continue(side)) => {
+                format!("{}の番です。", name(side))
+            }
+            (Lang::Japanese, GameStatus::PassBack(side)) => format!(
+                "{}はパスしました。{}の番です。",
+                name(side),
+                name(change_turn(side))
+            ),
+            (Lang::Japanese, GameStatus::GameOver(b, w)) => match b.cmp(&w) {
+                Ordering::Greater => {
+                    format!("ゲーム終了：{}の勝ち {}–{}。", black, b, w)
+                }
+                Ordering::Less => {
+                    format!("ゲーム終了：{}の勝ち {}–{}。", white, w, b)
+                }
+                Ordering::Equal => {
+                    format!("ゲーム終了：引き分け {}–{}。", b, w)
+                }
+            },
+            (Lang::Japanese, GameStatus::Resigned(side)) => format!(
+                "ゲーム終了：{}が投了し、{}の勝ちです。",
+                name(side),
+                name(change_turn(side))
+            ),
+            (Lang::Japanese, GameStatus::DrawAgreed) => {
+                "ゲーム終了：合意により引き分けです。".to_string()
+            }
+        }
+    }
+
+    /// Returns the disk counts shown above the status in the window.
+    pub fn counts(self, black: usize, white: usize) -> String {
+        match self {
+            Lang::English => format!("Black={}, White={}", black, white),
+            Lang::Japanese => format!("黒={}, 白={}", black, white),
+            Lang::Custom(catalog) => {
+                (catalog.translate)(&Lang::English.counts(black, white))
+            }
+        }
+    }
+
+    /// Returns the line editor prompt asking for a command.
+    pub fn command_prompt(self) -> String {
+        match self {
+            Lang::English => "Command? ".to_string(),
+            Lang::Japanese => "コマンド？ ".to_string(),
+            Lang::Custom(catalog) => (catalog.translate)("Command? "),
+        }
+    }
+
+    /// Returns the help text. It is not translated into Japanese yet.
+    pub fn help(self) -> String {
+        match self {
+            Lang::English | Lang::Japanese => HELP.to_string(),
+            Lang::Custom(catalog) => (catalog.translate)(HELP),
+        }
+    }
+
+    /// Puts a message in front of the status text.
+    pub fn prompt(self, message: Option<GameMessage>, status: &str) -> String {
+        match (self, message) {
+            (_, None) => status.to_string(),
+            (Lang::English, Some(message))
+            | (Lang::Custom(_), Some(message)) => {
+                format!("{} {}", self.message(message), status)
+            }
+            (Lang::Japanese, Some(message)) => {
+                format!("{}{}", self.message(message), status)
+            }
+        }
+    }
+}
+
+// =====================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{Catalog, GameMessage, Lang};
+    use crate::engine::{Command, Engine, GameStatus, Side};
+    use crate::gui;
+
+    static UPPERCASE: Catalog = Catalog {
+        code: "upper",
+        translate: str::to_uppercase,
+    };
+
+    #[test]
+    fn lang_parse() {
+        assert_eq!(Lang::parse("ja_JP.UTF-8"), Some(Lang::Japanese));
+        assert_eq!(Lang::parse("ja"), Some(Lang::Japanese));
+        assert_eq!(Lang::parse("en_US.UTF-8"), Some(Lang::English));
+        assert_eq!(Lang::parse("C"), Some(Lang::English));
+        assert_eq!(Lang::parse("fr_FR"), None);
+    }
+
+    #[test]
+    fn japanese_messages() {
+        let lang = Lang::Japanese;
+        let (black, white) = (lang.side_name(Side::Dark), "Bob");
+
+        let pass = GameStatus::PassBack(Side::Light);
+        let text = lang.status(pass, black, white);
+        assert_eq!(text, "Bobはパスしました。黒の番です。");
+        let over = GameStatus::GameOver(40, 24);
+        let text = lang.status(over, black, white);
+        assert_eq!(text, "ゲーム終了：黒の勝ち 40–24。");
+        let draw = GameStatus::GameOver(32, 32);
+        assert_eq!(
+            lang.status(draw, black, white),
+            "ゲーム終了：引き分け 32–32。"
+        );
+
+        let text = lang.prompt(Some(GameMessage::CantPass), "黒の番です。");
+        assert_eq!(text, "パスできません！黒の番です。");
+        let text = Lang::English.prompt(Some(GameMessage::Undo), "Go.");
+        assert_eq!(text, "Undo, and Go.");
+    }
+
+    #[test]
+    fn custom_catalog() {
+        let lang = Lang::Custom(&UPPERCASE);
+        assert_eq!(lang.command_prompt(), "COMMAND? ");
+        assert!(lang.help().contains("QUIT => QUIT THE PROGRAM"));
+
+        let mut engine = Engine::new();
+        engine.action(Command::Init);
+        assert_eq!(engine.prompt, "Game start! Black's turn.");
+        engine.set_lang(lang);
+        assert_eq!(engine.prompt, "GAME START! BLACK'S TURN.");
+        engine.set_name(Side::Light, "Bob");
+        engine.action(Command::Undo);
+        assert_eq!(engine.prompt, "CAN'T UNDO! BLACK'S TURN.");
+        assert_eq!(engine.name(Side::Light), "Bob");
+        assert_eq!(
+            gui::status_text(&engine),
+            "BLACK=2, WHITE=2\nCAN'T UNDO! BLACK'S TURN."
+        );
+        assert_eq!(lang.message(GameMessage::Thinking), "THINKING…");
+        let text = lang.message(GameMessage::Loaded(3));
+        assert_eq!(text, "LOADED 3 MOVES.");
+    }
+}
@@ -1,3 +1,5 @@
+use std::convert::TryFrom;
+use std::fmt;
 use std::hash::Hash;
 use std::ops;
 
@@ -14,6 +16,87 @@ impl Coordinate {
             row: Row::new(row),
         }
     }
+
+    /// Like `new`, but returns `None` for a column outside `a..=h` or a
+    /// row outside `1..=8` instead of panicking.
+    pub fn try_new(col: char, row: usize) -> Option<Coordinate> {
+        if ('a'..='h').contains(&col) && (1..=8).contains(&row) {
+            Some(Coordinate::new(col, row))
+        } else {
+            None
+        }
+    }
+
+    /// Iterates over all 64 coordinates in row-major order, from a1 to h8.
+    pub fn iter_all() -> impl Iterator<Item = Coordinate> {
+        Row::iter().flat_map(|row| {
+            Column::iter().map(move |col| Coordinate::from_parts(col, row))
+        })
+    }
+
+    pub fn from_parts(col: Column, row: Row) -> Coordinate {
+        Coordinate { col, row }
+    }
+
+    /// Returns the column letter, from `a` to `h`.
+    pub fn col(self) -> char {
+        self.col.0
+    }
+
+    /// Returns the row number, from 1 to 8.
+    pub fn row(self) -> u8 {
+        self.row.0 as u8
+    }
+
+    /// Returns zero-based `(column, row)` indices, so that `a1` is
+    /// `(0, 0)` and `h8` is `(7, 7)`.
+    pub fn indices(&self) -> (usize, usize) {
+        ((self.col.0 as u8 - b'a') as usize, self.row.0 - 1)
+    }
+
+    /// Returns the row-major index of the square, from 0 for `a1` to 63
+    /// for `h8`.
+    pub fn to_index(self) -> usize {
+        let (col, row) = self.indices();
+        row * 8 + col
+    }
+
+    /// Inverse of `to_index`, returning `None` for an index past 63.
+    pub fn from_index(index: usize) -> Option<Coordinate> {
+        if index >= 64 {
+            return None;
+        }
+
+        let col = (b'a' + (index % 8) as u8) as char;
+        Some(Coordinate::new(col, index / 8 + 1))
+    }
+
+    /// Returns the number of king moves between two squares.
+    pub fn chebyshev_distance(self, other: Coordinate) -> u8 {
+        let (col, row) = self.deltas(other);
+        col.max(row)
+    }
+
+    /// Returns the number of rook steps between two squares.
+    pub fn manhattan_distance(self, other: Coordinate) -> u8 {
+        let (col, row) = self.deltas(other);
+        col + row
+    }
+
+    /// Iterates over the squares around this one, up to eight of them,
+    /// starting from the one up and to the left.
+    pub fn neighbors_8(self) -> impl Iterator<Item = Coordinate> {
+        (-1..=1)
+            .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+            .filter(|&delta| delta != (0, 0))
+            .filter_map(move |delta| (self + delta).ok())
+    }
+
+    fn deltas(self, other: Coordinate) -> (u8, u8) {
+        let col = (self.col.to_u8() as i8 - other.col.to_u8() as i8).abs();
+        let row = (self.row.to_u8() as i8 - other.row.to_u8() as i8).abs();
+        (col as u8, row as u8)
+    }
 }
 
 impl ops::Add<(i32, i32)> for Coordinate {
@@ -27,12 +110,37 @@ impl ops::Add<(i32, i32)> for Coordinate {
     }
 }
 
+impl From<(char, i8)> for Coordinate {
+    /// Panics like `new` for a square off the board.
+    fn from((col, row): (char, i8)) -> Coordinate {
+        if row < 1 {
+            panic!("index out of bounds for Row");
+        }
+
+        Coordinate::new(col, row as usize)
+    }
+}
+
+impl TryFrom<(char, usize)> for Coordinate {
+    type Error = ();
+
+    fn try_from((col, row): (char, usize)) -> Result<Coordinate, ()> {
+        Coordinate::try_new(col, row).ok_or(())
+    }
+}
+
 impl Eq for Coordinate {}
 
+impl fmt::Display for Coordinate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.col.0, self.row.0)
+    }
+}
+
 // ---------------------------------------------------------------------
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq)]
-struct Column(char);
+pub struct Column(char);
 
 impl Column {
     fn new(index: char) -> Column {
@@ -42,6 +150,22 @@ impl Column {
 
         Column(index)
     }
+
+    /// Iterates over the columns from `a` to `h`.
+    pub fn iter() -> impl Iterator<Item = Column> {
+        ('a'..='h').map(Column)
+    }
+
+    /// Returns the zero-based index, 0 for `a` and 7 for `h`.
+    pub fn to_u8(self) -> u8 {
+        self.0 as u8 - b'a'
+    }
+}
+
+impl fmt::Display for Column {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
 }
 
 impl ops::Add<i32> for Column {
@@ -62,7 +186,7 @@ impl ops::Add<i32> for Column {
 // ---------------------------------------------------------------------
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq)]
-struct Row(usize);
+pub struct Row(usize);
 
 impl Row {
     fn new(index: usize) -> Row {
@@ -72,6 +196,22 @@ impl Row {
 
         Row(index)
     }
+
+    /// Iterates over the rows from 1 to 8.
+    pub fn iter() -> impl Iterator<Item = Row> {
+        (1..=8).map(Row)
+    }
+
+    /// Returns the zero-based index, 0 for row 1 and 7 for row 8.
+    pub fn to_u8(self) -> u8 {
+        self.0 as u8 - 1
+    }
+}
+
+impl fmt::Display for Row {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
 }
 
 impl ops::Add<i32> for Row {
@@ -97,6 +237,48 @@ mod tests {
     use super::Row;
 
     use super::Coordinate;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn coordinate_try_new() {
+        assert_eq!(Coordinate::try_new('a', 1), Some(Coordinate::new('a', 1)));
+        assert_eq!(Coordinate::try_new('h', 8), Some(Coordinate::new('h', 8)));
+        assert_eq!(Coordinate::try_new('i', 1), None);
+        assert_eq!(Coordinate::try_new('A', 1), None);
+        assert_eq!(Coordinate::try_new('a', 0), None);
+        assert_eq!(Coordinate::try_new('a', 9), None);
+    }
+
+    #[test]
+    fn coordinate_conversions() {
+        let c4 = Coordinate::new('c', 4);
+        assert_eq!((c4.col(), c4.row()), ('c', 4));
+        assert_eq!(Coordinate::from(('c', 4)), c4);
+        assert_eq!(Coordinate::try_from(('c', 4_usize)), Ok(c4));
+        assert_eq!(Coordinate::try_from(('c', 9_usize)), Err(()));
+        assert_eq!(Coordinate::try_from(('z', 1_usize)), Err(()));
+    }
+
+    #[test]
+    #[should_panic]
+    fn coordinate_from_negative_row() {
+        let _ = Coordinate::from(('a', -1));
+    }
+
+    #[test]
+    fn coordinate_distance() {
+        let a1 = Coordinate::new('a', 1);
+        let h8 = Coordinate::new('h', 8);
+        assert_eq!(a1.chebyshev_distance(h8), 7);
+        assert_eq!(a1.manhattan_distance(h8), 14);
+        assert_eq!(h8.chebyshev_distance(a1), 7);
+
+        let d4 = Coordinate::new('d', 4);
+        let e6 = Coordinate::new('e', 6);
+        assert_eq!(d4.chebyshev_distance(e6), 2);
+        assert_eq!(d4.manhattan_distance(e6), 3);
+        assert_eq!(d4.chebyshev_distance(d4), 0);
+    }
 
     #[test]
     fn coordinate_ops_add() {
@@ -118,6 +300,55 @@ mod tests {
         assert!((d4 + (0, -4)).is_err());
     }
 
+    #[test]
+    fn coordinate_neighbors_8() {
+        let d4 = Coordinate::new('d', 4);
+        let around: Vec<String> =
+            d4.neighbors_8().map(|coord| coord.to_string()).collect();
+        assert_eq!(around, ["c3", "d3", "e3", "c4", "e4", "c5", "d5", "e5"]);
+
+        let a1: Vec<Coordinate> =
+            Coordinate::new('a', 1).neighbors_8().collect();
+        assert_eq!(a1.len(), 3);
+        assert_eq!(Coordinate::new('h', 5).neighbors_8().count(), 5);
+    }
+
+    #[test]
+    fn coordinate_iter_all() {
+        let all: Vec<Coordinate> = Coordinate::iter_all().collect();
+        assert_eq!(all.len(), 64);
+        assert_eq!(all[0], Coordinate::new('a', 1));
+        assert_eq!(all[1], Coordinate::new('b', 1));
+        assert_eq!(all[8], Coordinate::new('a', 2));
+        assert_eq!(all[63], Coordinate::new('h', 8));
+    }
+
+    #[test]
+    fn coordinate_indices() {
+        assert_eq!(Coordinate::new('a', 1).indices(), (0, 0));
+        assert_eq!(Coordinate::new('c', 5).indices(), (2, 4));
+        assert_eq!(Coordinate::new('h', 8).indices(), (7, 7));
+    }
+
+    #[test]
+    fn coordinate_index() {
+        for index in 0..64 {
+            let coord = Coordinate::from_index(index).unwrap();
+            assert_eq!(coord.to_index(), index);
+        }
+        assert_eq!(Coordinate::new('a', 1).to_index(), 0);
+        assert_eq!(Coordinate::new('b', 1).to_index(), 1);
+        assert_eq!(Coordinate::new('a', 2).to_index(), 8);
+        assert_eq!(Coordinate::new('h', 8).to_index(), 63);
+        assert_eq!(Coordinate::from_index(64), None);
+    }
+
+    #[test]
+    fn coordinate_display() {
+        assert_eq!(Coordinate::new('a', 1).to_string(), "a1");
+        assert_eq!(Coordinate::new('h', 8).to_string(), "h8");
+    }
+
     // ---------------------------------------------------------
 
     #[test]
@@ -140,6 +371,16 @@ mod tests {
         }
     }
 
+    #[test]
+    fn column_iter() {
+        let columns: Vec<Column> = Column::iter().collect();
+        assert_eq!(columns.len(), 8);
+        assert_eq!(columns[0], Column('a'));
+        assert_eq!(columns[7], Column('h'));
+        let indices: Vec<u8> = columns.iter().map(|col| col.to_u8()).collect();
+        assert_eq!(indices, (0..8).collect::<Vec<u8>>());
+    }
+
     #[test]
     fn column_ops_add() {
         let col_e = Column::new('e');
@@ -178,6 +419,16 @@ mod tests {
         }
     }
 
+    #[test]
+    fn row_iter() {
+        let rows: Vec<Row> = Row::iter().collect();
+        assert_eq!(rows.len(), 8);
+        assert_eq!(rows[0], Row(1));
+        assert_eq!(rows[7], Row(8));
+        let indices: Vec<u8> = rows.iter().map(|row| row.to_u8()).collect();
+        assert_eq!(indices, (0..8).collect::<Vec<u8>>());
+    }
+
     #[test]
     fn row_ops_add() {
         let row4 = Row::new(4);
@@ -0,0 +1,189 @@
+use crate::board::{Board, Disk};
+use crate::position::Coordinate;
+#[cfg(feature = "png-export")]
+use std::fs::File;
+#[cfg(feature = "png-export")]
+use std::io::{self, BufWriter};
+
+pub const BACKGROUND: [u8; 4] = [0, 128, 0, 255];
+pub const GRID: [u8; 4] = [0, 64, 0, 255];
+pub const BLACK: [u8; 4] = [0, 0, 0, 255];
+pub const WHITE: [u8; 4] = [255, 255, 255, 255];
+
+impl Board {
+    /// Draws the board as RGBA pixels, row by row from a1 at the top
+    /// left, each square `cell_size` pixels wide: a green grid with a
+    /// circle for each disk.
+    pub fn to_image_buffer(&self, cell_size: u32) -> Vec<u8> {
+        let mut image = Image::new(8 * cell_size);
+        for i in (0..image.size).step_by(cell_size.max(1) as usize) {
+            image.fill_row(i, 0, image.size - 1, GRID);
+            image.fill_column(i, GRID);
+        }
+
+        for coord in Coordinate::iter_all() {
+            let color = match self.get_disk(coord) {
+                Some(Disk::Black) => BLACK,
+                Some(Disk::White) => WHITE,
+                None => continue,
+            };
+            let (col, row) = coord.indices();
+            let center =
+                |index: usize| index as u32 * cell_size + cell_size / 2;
+            image.fill_circle(
+                center(col),
+                center(row),
+                cell_size * 2 / 5,
+                color,
+            );
+        }
+
+        image.pixels
+    }
+
+    /// Saves the board drawn by `to_image_buffer` as a PNG file.
+    #[cfg(feature = "png-export")]
+    pub fn to_png(&self, cell_size: u32, path: &str) -> io::Result<()> {
+        let size = 8 * cell_size;
+        let file = BufWriter::new(File::create(path)?);
+        let mut encoder = png::Encoder::new(file, size, size);
+        encoder.set_color(png::ColorType::RGBA);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.to_image_buffer(cell_size))?;
+
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------
+
+/// A square RGBA image that starts out filled with the background.
+struct Image {
+    size: u32,
+    pixels: Vec<u8>,
+}
+
+impl Image {
+    fn new(size: u32) -> Image {
+        let count = (size * size) as usize;
+        Image {
+            size,
+            pixels: BACKGROUND.repeat(count),
+        }
+    }
+
+    fn set(&mut self, x: u32, y: u32, color: [u8; 4]) {
+        if x < self.size && y < self.size {
+            let index = ((y * self.size + x) * 4) as usize;
+            self.pixels[index..index + 4].copy_from_slice(&color);
+        }
+    }
+
+    /// Colors row `y` from `left` to `right`, both included.
+    fn fill_row(&mut self, y: u32, left: u32, right: u32, color: [u8; 4]) {
+        for x in left..=right {
+            self.set(x, y, color);
+        }
+    }
+
+    fn fill_column(&mut self, x: u32, color: [u8; 4]) {
+        for y in 0..self.size {
+            self.set(x, y, color);
+        }
+    }
+
+    /// Fills a circle with Bresenham's midpoint algorithm, drawing a
+    /// row across it for each point found on the first octant.
+    fn fill_circle(&mut self, cx: u32, cy: u32, radius: u32, color: [u8; 4]) {
+        let (cx, cy) = (i64::from(cx), i64::from(cy));
+        let mut x = i64::from(radius);
+        let mut y = 0;
+        let mut error = 1 - x;
+        while x >= y {
+            for &(dx, dy) in &[(x, y), (x, -y), (y, x), (y, -x)] {
+                let row = cy + dy;
+                if row >= 0 && cx - dx >= 0 {
+                    let (left, right) = ((cx - dx) as u32, (cx + dx) as u32);
+                    self.fill_row(row as u32, left, right, color);
+                }
+            }
+
+            y += 1;
+            if error < 0 {
+                error += 2 * y + 1;
+            } else {
+                x -= 1;
+                error += 2 * (y - x) + 1;
+            }
+        }
+    }
+}
+
+// =====================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{BACKGROUND, BLACK, WHITE};
+    use crate::board::Board;
+
+    fn pixel(buffer: &[u8], size: u32, x: u32, y: u32) -> [u8; 4] {
+        let index = ((y * size + x) * 4) as usize;
+        [
+            buffer[index],
+            buffer[index + 1],
+            buffer[index + 2],
+            buffer[index + 3],
+        ]
+    }
+
+    #[test]
+    fn image_buffer_corners() {
+        let board: Board = "\
+x....... ........ ........ ........ ........ ........ ........ .......o"
+            .parse()
+            .unwrap();
+        for &cell in &[1, 10, 25] {
+            let buffer = board.to_image_buffer(cell);
+            let size = 8 * cell;
+            assert_eq!(buffer.len(), (size * size * 4) as usize);
+
+            let center = |index: u32| index * cell + cell / 2;
+            assert_eq!(pixel(&buffer, size, center(0), center(0)), BLACK);
+            assert_eq!(pixel(&buffer, size, center(7), center(7)), WHITE);
+            if cell > 1 {
+                let empty = pixel(&buffer, size, center(7), center(0));
+                assert_eq!(empty, BACKGROUND);
+                assert_eq!(pixel(&buffer, size, center(0), center(7)), empty);
+            }
+        }
+    }
+
+    #[test]
+    fn image_buffer_circles() {
+        let board: Board = "\
+x....... ........ ........ ........ ........ ........ ........ ........"
+            .parse()
+            .unwrap();
+        let buffer = board.to_image_buffer(20);
+        // The disk reaches 8 pixels out from the center at (10, 10).
+        assert_eq!(pixel(&buffer, 160, 18, 10), BLACK);
+        assert_eq!(pixel(&buffer, 160, 10, 2), BLACK);
+        assert_eq!(pixel(&buffer, 160, 19, 10), BACKGROUND);
+        assert_eq!(pixel(&buffer, 160, 16, 16), BACKGROUND);
+        assert_eq!(pixel(&buffer, 160, 15, 15), BLACK);
+    }
+
+    #[cfg(feature = "png-export")]
+    #[test]
+    fn png_file() {
+        let mut board = Board::new();
+        board.init();
+        let path = std::env::temp_dir().join("othello-render-test.png");
+        let path = path.to_str().unwrap();
+        board.to_png(4, path).unwrap();
+        let data = std::fs::read(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert!(data.starts_with(b"\x89PNG\r\n"));
+    }
+}
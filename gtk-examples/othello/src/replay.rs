@@ -0,0 +1,281 @@
+use crate::engine::{Command, Engine, Side};
+use crate::position::Coordinate;
+use std::fmt;
+
+pub struct GameRecord {
+    pub moves: Vec<Option<Coordinate>>,
+    pub starting_side: Side,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum TranscriptError {
+    InvalidToken(usize, String),
+    Replay(ReplayError),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ReplayError {
+    pub step: usize,
+    pub coord: Option<Coordinate>,
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.coord {
+            Some(coord) => {
+                write!(f, "Illegal move {} at step {}", coord, self.step + 1)
+            }
+            None => write!(f, "Illegal pass at step {}", self.step + 1),
+        }
+    }
+}
+
+impl fmt::Display for TranscriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TranscriptError::InvalidToken(step, token) => {
+                write!(f, "Invalid token {:?} at step {}", token, step + 1)
+            }
+            TranscriptError::Replay(error) => error.fmt(f),
+        }
+    }
+}
+
+impl Engine {
+    pub fn from_game_record(
+        record: &GameRecord,
+    ) -> Result<Engine, ReplayError> {
+        let mut engine = Engine::with_turn(record.starting_side);
+        engine.action(Command::Init);
+        engine.replay(&record.moves)?;
+
+        Ok(engine)
+    }
+
+    /// Writes the moves played so far as concatenated coordinates, such
+    /// as `f5d6c3`, with `--` for a pass.
+    pub fn transcript(&self) -> String {
+        self.move_history()
+            .iter()
+            .map(|coord| match coord {
+                Some(coord) => coord.to_string(),
+                None => "--".to_string(),
+            })
+            .collect()
+    }
+
+    /// Restarts the game and replays a transcript. Whitespace between
+    /// tokens is ignored and a pass may be written as `--` or `ps`. The
+    /// game in progress is restored if the transcript is invalid.
+    pub fn load_transcript(
+        &mut self,
+        transcript: &str,
+    ) -> Result<(), TranscriptError> {
+        let moves = parse_transcript(transcript)?;
+        let previous = self.move_history();
+        self.action(Command::Init);
+
+        if let Err(err) = self.replay(&moves) {
+            self.action(Command::Init);
+            self.replay(&previous)
+                .expect("moves already played are legal");
+            return Err(TranscriptError::Replay(err));
+        }
+
+        Ok(())
+    }
+
+    fn replay(
+        &mut self,
+        moves: &[Option<Coordinate>],
+    ) -> Result<(), ReplayError> {
+        for (step, &coord) in moves.iter().enumerate() {
+            let error = ReplayError { step, coord };
+
+            // A forced pass has already been played by the engine.
+            let history = self.move_history();
+            if step < history.len() {
+                if history[step] == coord {
+                    continue;
+                }
+                return Err(error);
+            }
+
+            match coord {
+                Some(coord) => self.action(Command::Move(coord)),
+                None => self.action(Command::Pass),
+            }
+            if self.move_history().len() == step {
+                return Err(error);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_transcript(
+    transcript: &str,
+) -> Result<Vec<Option<Coordinate>>, TranscriptError> {
+    let chars: Vec<char> =
+        transcript.chars().filter(|c| !c.is_whitespace()).collect();
+
+    let mut moves = Vec::with_capacity(chars.len() / 2);
+    for (step, token) in chars.chunks(2).enumerate() {
+        let token: String = token.iter().collect();
+        let coord = match token.to_lowercase().as_str() {
+            "--" | "ps" => None,
+            _ => match parse_token(&token) {
+                Some(coord) => Some(coord),
+                None => {
+                    return Err(TranscriptError::InvalidToken(step, token))
+                }
+            },
+        };
+        moves.push(coord);
+    }
+
+    Ok(moves)
+}
+
+fn parse_token(token: &str) -> Option<Coordinate> {
+    let mut chars = token.chars();
+    let col = chars.next()?.to_ascii_lowercase();
+    let row = chars.next()?.to_digit(10)? as usize;
+    Coordinate::try_new(col, row)
+}
+
+// =====================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{GameRecord, ReplayError, TranscriptError};
+    use crate::board::Board;
+    use crate::engine::{Command, Engine, GameStatus, Side};
+    use crate::position::Coordinate;
+
+    fn play_to_end(engine: &mut Engine) {
+        'game: loop {
+            for coord in Coordinate::iter_all() {
+                let before = engine.move_history().len();
+                engine.action(Command::Move(coord));
+                if engine.move_history().len() > before {
+                    continue 'game;
+                }
+            }
+            break;
+        }
+    }
+
+    #[test]
+    fn replay_game_record() {
+        let mut engine = Engine::new();
+        engine.action(Command::Init);
+        play_to_end(&mut engine);
+
+        let record = GameRecord {
+            moves: engine.move_history(),
+            starting_side: Side::Dark,
+        };
+        let replayed = Engine::from_game_record(&record).unwrap();
+        assert_eq!(
+            replayed.current_board().render_compact(),
+            engine.current_board().render_compact()
+        );
+        assert_eq!(replayed.move_history(), record.moves);
+    }
+
+    #[test]
+    fn replay_illegal_move() {
+        let f5 = Coordinate::new('f', 5);
+        let record = GameRecord {
+            moves: vec![Some(f5), Some(f5)],
+            starting_side: Side::Dark,
+        };
+        let error = Engine::from_game_record(&record).err().unwrap();
+        assert_eq!(
+            error,
+            ReplayError {
+                step: 1,
+                coord: Some(f5)
+            }
+        );
+        assert_eq!(error.to_string(), "Illegal move f5 at step 2");
+
+        let record = GameRecord {
+            moves: vec![None],
+            starting_side: Side::Dark,
+        };
+        let error = Engine::from_game_record(&record).err().unwrap();
+        assert_eq!(error.to_string(), "Illegal pass at step 1");
+    }
+
+    #[test]
+    fn transcript_round_trip() {
+        let mut engine = Engine::new();
+        engine.action(Command::Init);
+        play_to_end(&mut engine);
+
+        let transcript = engine.transcript();
+        let mut loaded = Engine::new();
+        loaded.load_transcript(&transcript).unwrap();
+        assert_eq!(loaded.current_board(), engine.current_board());
+        assert_eq!(loaded.transcript(), transcript);
+    }
+
+    #[test]
+    fn transcript_with_pass() {
+        let board: Board = "\
+xo.....x .......x .......x .......x .......x .......x .......o ........"
+            .parse()
+            .unwrap();
+        let mut engine = Engine::from_board(board.clone(), Side::Dark);
+        engine.action(Command::Init);
+        engine.action(Command::Move(Coordinate::new('c', 1)));
+        assert_eq!(engine.transcript(), "c1--");
+
+        let mut loaded = Engine::from_board(board.clone(), Side::Dark);
+        loaded.load_transcript("c1--").unwrap();
+        assert_eq!(loaded.current_board(), engine.current_board());
+        assert_eq!(loaded.status(), engine.status());
+        assert_eq!(loaded.status(), GameStatus::PassBack(Side::Light));
+
+        engine.action(Command::Move(Coordinate::new('h', 8)));
+        assert_eq!(engine.transcript(), "c1--h8");
+        let mut loaded = Engine::from_board(board, Side::Dark);
+        loaded.load_transcript("c1 ps h8").unwrap();
+        assert_eq!(loaded.current_board(), engine.current_board());
+        assert_eq!(loaded.status(), engine.status());
+    }
+
+    #[test]
+    fn transcript_with_leading_pass() {
+        let board: Board = "\
+ox...... ........ ........ ........ ........ ........ ........ ........"
+            .parse()
+            .unwrap();
+        let mut engine = Engine::from_board(board, Side::Dark);
+        engine.load_transcript("--c1").unwrap();
+        assert_eq!(engine.transcript(), "--c1");
+        assert_eq!(engine.status(), GameStatus::GameOver(0, 3));
+    }
+
+    #[test]
+    fn transcript_errors() {
+        let mut engine = Engine::new();
+        engine.action(Command::Init);
+        engine.action(Command::Move(Coordinate::new('f', 5)));
+
+        let result = engine.load_transcript("f5d6z9");
+        let error = TranscriptError::InvalidToken(2, "z9".to_string());
+        assert_eq!(result, Err(error));
+
+        let result = engine.load_transcript("f5--");
+        let error = ReplayError {
+            step: 1,
+            coord: None,
+        };
+        assert_eq!(result, Err(TranscriptError::Replay(error)));
+        assert_eq!(engine.transcript(), "f5");
+    }
+}
@@ -0,0 +1,457 @@
+use crate::board::{flip_disk, Board, Disk};
+use crate::book::OpeningBook;
+use crate::heuristic::composite_score;
+use crate::position::Coordinate;
+use std::cmp::Reverse;
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+const WIN_SCORE: i32 = 1000;
+const INFINITY: i32 = 2 * WIN_SCORE;
+
+/// A horizon node is searched further only if its best move flips more
+/// disks than this.
+pub const QUIESCENCE_FLIP_THRESHOLD: usize = 5;
+/// Percentage of occupied squares from which quiescence search stops
+/// extending, since stability matters more than flips near the end.
+pub const QUIESCENCE_DENSITY_LIMIT: usize = 90;
+/// Maximum number of plies quiescence search extends beyond the horizon.
+pub const QUIESCENCE_MAX_PLIES: u8 = 2;
+/// Weight of the edge stability difference once more than
+/// `EDGE_STABILITY_DENSITY` percent of the squares are occupied.
+pub const EDGE_STABILITY_WEIGHT: i32 = 7;
+pub const EDGE_STABILITY_DENSITY: usize = 40;
+/// Weight of the edge holes where a side may wedge in, counted against
+/// the side whose edge has them.
+pub const WEDGE_WEIGHT: i32 = 3;
+
+#[derive(Debug, Default)]
+pub struct SearchStats {
+    pub nodes: u64,
+    pub quiescence_calls: u64,
+}
+
+/// Runs `best_move`, or `best_move_with_book` when given a book, on
+/// another thread and hands the result to `deliver`, so that a front-end
+/// can keep responding while the computer thinks.
+pub fn spawn_best_move<F>(
+    board: Board,
+    disk: Disk,
+    depth: u8,
+    book: Option<Arc<OpeningBook>>,
+    deliver: F,
+) -> JoinHandle<()>
+where
+    F: FnOnce(Option<Coordinate>) + Send + 'static,
+{
+    thread::spawn(move || {
+        deliver(match book {
+            Some(book) => best_move_with_book(&board, disk, depth, &book),
+            None => best_move(&board, disk, depth),
+        })
+    })
+}
+
+pub fn best_move(board: &Board, disk: Disk, depth: u8) -> Option<Coordinate> {
+    let depth = depth.max(1);
+    let opponent = flip_disk(&disk);
+    let mut stats = SearchStats::default();
+    let mut alpha = -INFINITY;
+    let mut best = None;
+
+    for coord in board.valid_moves(disk) {
+        let next = board.try_move(coord, disk).unwrap();
+        let score = -alpha_beta(
+            &next,
+            opponent,
+            depth - 1,
+            -INFINITY,
+            -alpha,
+            &mut stats,
+        );
+        if best.is_none() || score > alpha {
+            alpha = score;
+            best = Some(coord);
+        }
+    }
+
+    best
+}
+
+/// Plays the move booked for the position if there is one, and searches
+/// like `best_move` otherwise.
+pub fn best_move_with_book(
+    board: &Board,
+    disk: Disk,
+    depth: u8,
+    book: &OpeningBook,
+) -> Option<Coordinate> {
+    book.book_move(board, disk)
+        .or_else(|| best_move(board, disk, depth))
+}
+
+/// Scores every legal move of `disk` by searching `depth` plies with a
+/// full window, best first and in row-major order among equal scores.
+/// Slower than `best_move`, which only needs the best one.
+pub fn score_moves(
+    board: &Board,
+    disk: Disk,
+    depth: u8,
+) -> Vec<(Coordinate, i32)> {
+    let depth = depth.max(1);
+    let opponent = flip_disk(&disk);
+    let mut stats = SearchStats::default();
+    let mut scores: Vec<(Coordinate, i32)> = board
+        .valid_moves(disk)
+        .into_iter()
+        .map(|coord| {
+            let next = board.try_move(coord, disk).unwrap();
+            let score = -alpha_beta(
+                &next,
+                opponent,
+                depth - 1,
+                -INFINITY,
+                INFINITY,
+                &mut stats,
+            );
+            (coord, score)
+        })
+        .collect();
+    scores.sort_by_key(|&(_, score)| Reverse(score));
+
+    scores
+}
+
+/// Returns the line expected after `disk` plays `coord`: the move, then
+/// the best reply found at each following ply by a search one ply
+/// shallower, `depth` moves in all unless the game ends first. A pass is
+/// `None`, and an illegal `coord` gives an empty line.
+pub fn principal_variation(
+    board: &Board,
+    disk: Disk,
+    coord: Coordinate,
+    depth: u8,
+) -> Vec<Option<Coordinate>> {
+    let mut board = match board.try_move(coord, disk) {
+        Ok(board) => board,
+        Err(_) => return Vec::new(),
+    };
+    let mut line = vec![Some(coord)];
+    let mut disk = flip_disk(&disk);
+    for remaining in (1..depth).rev() {
+        match best_move(&board, disk, remaining) {
+            Some(coord) => {
+                board = board.try_move(coord, disk).unwrap();
+                line.push(Some(coord));
+            }
+            None if board.valid_moves(flip_disk(&disk)).is_empty() => break,
+            None => line.push(None),
+        }
+        disk = flip_disk(&disk);
+    }
+
+    line
+}
+
+pub fn evaluate(board: &Board, disk: Disk) -> i32 {
+    let score =
+        composite_score(board, disk) + WEDGE_WEIGHT * wedges(board, disk);
+
+    if density(board) > EDGE_STABILITY_DENSITY {
+        score + EDGE_STABILITY_WEIGHT * edge_stability(board, disk)
+    } else {
+        score
+    }
+}
+
+/// Wedges open to `disk` less those open to its opponent.
+fn wedges(board: &Board, disk: Disk) -> i32 {
+    i32::from(board.count_wedges(disk))
+        - i32::from(board.count_wedges(flip_disk(&disk)))
+}
+
+/// Stable edge disks of `disk` less those of its opponent.
+fn edge_stability(board: &Board, disk: Disk) -> i32 {
+    i32::from(board.edge_stability_count(disk))
+        - i32::from(board.edge_stability_count(flip_disk(&disk)))
+}
+
+fn disk_difference(board: &Board, disk: Disk) -> i32 {
+    board.count(disk) as i32 - board.count(flip_disk(&disk)) as i32
+}
+
+pub fn alpha_beta(
+    board: &Board,
+    disk: Disk,
+    depth: u8,
+    mut alpha: i32,
+    beta: i32,
+    stats: &mut SearchStats,
+) -> i32 {
+    stats.nodes += 1;
+    if depth == 0 {
+        stats.quiescence_calls += 1;
+        return quiescence_search(
+            board,
+            disk,
+            alpha,
+            beta,
+            QUIESCENCE_MAX_PLIES,
+            stats,
+        );
+    }
+
+    let opponent = flip_disk(&disk);
+    let moves = board.valid_moves(disk);
+    if moves.is_empty() {
+        if board.valid_moves(opponent).is_empty() {
+            return final_score(board, disk);
+        }
+        return -alpha_beta(board, opponent, depth - 1, -beta, -alpha, stats);
+    }
+
+    for coord in moves {
+        let next = board.try_move(coord, disk).unwrap();
+        let score =
+            -alpha_beta(&next, opponent, depth - 1, -beta, -alpha, stats);
+        if score > alpha {
+            alpha = score;
+        }
+        if alpha >= beta {
+            break;
+        }
+    }
+
+    alpha
+}
+
+pub fn quiescence_search(
+    board: &Board,
+    disk: Disk,
+    mut alpha: i32,
+    beta: i32,
+    plies: u8,
+    stats: &mut SearchStats,
+) -> i32 {
+    stats.nodes += 1;
+    let stand_pat = evaluate(board, disk);
+    if plies == 0 || density(board) >= QUIESCENCE_DENSITY_LIMIT {
+        return stand_pat;
+    }
+
+    let moves = board.count_flips(&board.valid_moves(disk), disk);
+    let max_flips = moves.first().map(|&(_, flips)| flips);
+    if max_flips.unwrap_or(0) <= QUIESCENCE_FLIP_THRESHOLD {
+        return stand_pat;
+    }
+
+    let opponent = flip_disk(&disk);
+    for (coord, _) in moves {
+        let next = board.try_move(coord, disk).unwrap();
+        let score = -quiescence_search(
+            &next,
+            opponent,
+            -beta,
+            -alpha,
+            plies - 1,
+            stats,
+        );
+        if score > alpha {
+            alpha = score;
+        }
+        if alpha >= beta {
+            break;
+        }
+    }
+
+    alpha
+}
+
+fn density(board: &Board) -> usize {
+    (board.count(Disk::Black) + board.count(Disk::White)) * 100 / 64
+}
+
+fn final_score(board: &Board, disk: Disk) -> i32 {
+    let diff = disk_difference(board, disk);
+    if diff > 0 {
+        WIN_SCORE + diff
+    } else if diff < 0 {
+        -WIN_SCORE + diff
+    } else {
+        0
+    }
+}
+
+// =====================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{alpha_beta, best_move, evaluate, quiescence_search};
+    use super::{principal_variation, EDGE_STABILITY_WEIGHT};
+    use super::{spawn_best_move, SearchStats, INFINITY};
+    use crate::board::{Board, Disk};
+    use crate::book::OpeningBook;
+    use crate::heuristic::ENDGAME_WEIGHTS;
+    use crate::position::Coordinate;
+    use std::sync::{mpsc, Arc};
+
+    #[test]
+    fn evaluate_opening_by_weights() {
+        let mut board = Board::new();
+        board.init();
+        assert_eq!(evaluate(&board, Disk::Black), 0);
+
+        // Weights give black 1, and potential mobility 5 against 13
+        // takes 3 * 8 off.
+        let board = board.try_move(Coordinate::new('f', 5), Disk::Black);
+        let board = board.unwrap();
+        assert_eq!(evaluate(&board, Disk::Black), -23);
+        assert_eq!(evaluate(&board, Disk::White), 23);
+    }
+
+    #[test]
+    fn evaluate_later_by_disk_difference() {
+        let board: Board = "\
+xxxxxxxx xxxxxxxx xxxxxxxx xxxxxxxx oooooooo oooooooo ........ ........"
+            .parse()
+            .unwrap();
+        // Black holds the top edge and half of the side ones.
+        let stability = 14 * EDGE_STABILITY_WEIGHT;
+        assert_eq!(evaluate(&board, Disk::Black), 16 + stability);
+        assert_eq!(evaluate(&board, Disk::White), -16 - stability);
+
+        let board: Board = "\
+xxxxxxxx xxxxxxxx xxxxxxxx xxxxxxxx oooooooo oooooooo o....... ........"
+            .parse()
+            .unwrap();
+        let score = 15 + ENDGAME_WEIGHTS.parity + stability;
+        assert_eq!(evaluate(&board, Disk::Black), score);
+        let score = -15 + ENDGAME_WEIGHTS.parity - stability;
+        assert_eq!(evaluate(&board, Disk::White), score);
+    }
+
+    #[test]
+    fn spawn_best_move_delivers() {
+        let mut board = Board::new();
+        board.init();
+        let expected = best_move(&board, Disk::Black, 2);
+
+        let (sender, receiver) = mpsc::channel();
+        let handle = spawn_best_move(
+            board.clone(),
+            Disk::Black,
+            2,
+            None,
+            move |coord| {
+                sender.send(coord).unwrap();
+            },
+        );
+        assert_eq!(receiver.recv().unwrap(), expected);
+        assert!(expected.is_some());
+        handle.join().unwrap();
+
+        // The book has f5, which the search doesn't pick at depth 2.
+        let book = Arc::new(OpeningBook::load("f5d6").unwrap());
+        let (sender, receiver) = mpsc::channel();
+        let handle =
+            spawn_best_move(board, Disk::Black, 2, Some(book), move |coord| {
+                sender.send(coord).unwrap();
+            });
+        assert_eq!(receiver.recv().unwrap(), Some(Coordinate::new('f', 5)));
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn best_move_takes_winning_corner() {
+        let board: Board = "\
+.ox..... ........ ........ ........ ........ ........ ........ ........"
+            .parse()
+            .unwrap();
+        for depth in 1..=3 {
+            let coord = best_move(&board, Disk::White, depth);
+            assert_eq!(coord, Some(Coordinate::new('d', 1)));
+        }
+        let coord = best_move(&board, Disk::Black, 1);
+        assert_eq!(coord, Some(Coordinate::new('a', 1)));
+    }
+
+    #[test]
+    fn quiescence_only_at_horizon() {
+        let mut board = Board::new();
+        board.init();
+
+        let mut stats = SearchStats::default();
+        alpha_beta(&board, Disk::Black, 1, -INFINITY, INFINITY, &mut stats);
+        assert_eq!(stats.quiescence_calls, 4);
+
+        let mut stats = SearchStats::default();
+        alpha_beta(&board, Disk::Black, 0, -INFINITY, INFINITY, &mut stats);
+        assert_eq!(stats.quiescence_calls, 1);
+
+        let full: Board = "\
+xxxxxxxx xxxxxxxx xxxxxxxx xxxxxxxx oooooooo oooooooo oooooooo oooooooo"
+            .parse()
+            .unwrap();
+        let mut stats = SearchStats::default();
+        let score =
+            alpha_beta(&full, Disk::Black, 3, -INFINITY, INFINITY, &mut stats);
+        assert_eq!(score, 0);
+        assert_eq!(stats.quiescence_calls, 0);
+    }
+
+    #[test]
+    fn quiescence_extends_volatile_positions() {
+        let board: Board = "\
+xoooooo. ........ ........ ........ ........ ........ ........ ........"
+            .parse()
+            .unwrap();
+        let mut stats = SearchStats::default();
+        let score = quiescence_search(
+            &board,
+            Disk::Black,
+            -INFINITY,
+            INFINITY,
+            2,
+            &mut stats,
+        );
+        assert!(stats.nodes > 1);
+        let next = board.try_move(Coordinate::new('h', 1), Disk::Black);
+        assert_eq!(score, evaluate(&next.unwrap(), Disk::Black));
+
+        let mut stats = SearchStats::default();
+        let score = quiescence_search(
+            &board,
+            Disk::Black,
+            -INFINITY,
+            INFINITY,
+            0,
+            &mut stats,
+        );
+        assert_eq!(stats.nodes, 1);
+        assert_eq!(score, evaluate(&board, Disk::Black));
+    }
+
+    #[test]
+    fn principal_variation_lines() {
+        let mut board = Board::new();
+        board.init();
+        let f5 = Coordinate::new('f', 5);
+        let line = principal_variation(&board, Disk::Black, f5, 3);
+        assert_eq!(line.len(), 3);
+        assert_eq!(line[0], Some(f5));
+        let next = board.try_move(f5, Disk::Black).unwrap();
+        assert_eq!(line[1], best_move(&next, Disk::White, 2));
+
+        let a1 = Coordinate::new('a', 1);
+        assert!(principal_variation(&board, Disk::Black, a1, 3).is_empty());
+
+        // White has to pass after c1, and black ends the game with h8.
+        let board: Board = "\
+xo.....x .......x .......x .......x .......x .......x .......o ........"
+            .parse()
+            .unwrap();
+        let c1 = Coordinate::new('c', 1);
+        let line = principal_variation(&board, Disk::Black, c1, 4);
+        assert_eq!(&line[..2], &[Some(c1), None]);
+    }
+}
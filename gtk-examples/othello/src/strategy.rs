@@ -0,0 +1,266 @@
+use crate::board::Board;
+use crate::book::OpeningBook;
+use crate::cui::parse_coordinate;
+use crate::engine::Side;
+use crate::position::Coordinate;
+use crate::search;
+use rand::rngs::SmallRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::io::{self, Write};
+use std::sync::Arc;
+
+/// Chooses a move for one side. `valid_moves` is empty when the side must
+/// pass, and `None` is returned in that case.
+pub trait Strategy {
+    fn choose_move(
+        &mut self,
+        board: &Board,
+        side: Side,
+        valid_moves: &[Coordinate],
+    ) -> Option<Coordinate>;
+
+    /// Whether moves come from the player at the terminal, in which case
+    /// the CUI reads them as commands instead of calling `choose_move`.
+    fn is_human(&self) -> bool {
+        false
+    }
+}
+
+/// Parses a player description: `human`, `random`, `greedy` or
+/// `ai:<depth>`, which plays from `book` when one is given.
+pub fn parse_strategy(
+    name: &str,
+    book: Option<&Arc<OpeningBook>>,
+) -> Option<Box<dyn Strategy>> {
+    let mut parts = name.splitn(2, ':');
+    match (parts.next()?, parts.next()) {
+        ("human", None) => Some(Box::new(HumanStrategy)),
+        ("random", None) => Some(Box::new(RandomStrategy::new())),
+        ("greedy", None) => Some(Box::new(GreedyStrategy)),
+        ("ai", Some(depth)) => match depth.parse::<u8>() {
+            Ok(depth) if depth > 0 => Some(minimax(depth, book)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Returns the computer player searching `depth` plies ahead, which
+/// plays from `book` first when there is one.
+pub fn minimax(
+    depth: u8,
+    book: Option<&Arc<OpeningBook>>,
+) -> Box<dyn Strategy> {
+    match book {
+        Some(book) => Box::new(BookStrategy::new(Arc::clone(book), depth)),
+        None => Box::new(AiStrategy(depth)),
+    }
+}
+
+// ---------------------------------------------------------------------
+
+pub struct HumanStrategy;
+
+impl Strategy for HumanStrategy {
+    fn choose_move(
+        &mut self,
+        _board: &Board,
+        side: Side,
+        valid_moves: &[Coordinate],
+    ) -> Option<Coordinate> {
+        if valid_moves.is_empty() {
+            return None;
+        }
+
+        let mut buffer = String::new();
+        loop {
+            print!("Move for {}? ", side);
+            io::stdout().flush().ok()?;
+            buffer.clear();
+            if io::stdin().read_line(&mut buffer).ok()? == 0 {
+                return None;
+            }
+
+            match parse_coordinate(Some(buffer.trim())) {
+                Ok(coord) if valid_moves.contains(&coord) => {
+                    return Some(coord)
+                }
+                Ok(coord) => println!("Can't place at {}", coord),
+                Err(err) => println!("{}", err),
+            }
+        }
+    }
+
+    fn is_human(&self) -> bool {
+        true
+    }
+}
+
+// ---------------------------------------------------------------------
+
+pub struct RandomStrategy {
+    rng: SmallRng,
+}
+
+impl RandomStrategy {
+    pub fn new() -> RandomStrategy {
+        RandomStrategy {
+            rng: SmallRng::from_entropy(),
+        }
+    }
+
+    pub fn with_seed(seed: u64) -> RandomStrategy {
+        RandomStrategy {
+            rng: SmallRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Default for RandomStrategy {
+    fn default() -> RandomStrategy {
+        RandomStrategy::new()
+    }
+}
+
+impl Strategy for RandomStrategy {
+    fn choose_move(
+        &mut self,
+        _board: &Board,
+        _side: Side,
+        valid_moves: &[Coordinate],
+    ) -> Option<Coordinate> {
+        valid_moves.choose(&mut self.rng).copied()
+    }
+}
+
+// ---------------------------------------------------------------------
+
+/// Takes the move flipping the most disks, the first one on a tie.
+pub struct GreedyStrategy;
+
+impl Strategy for GreedyStrategy {
+    fn choose_move(
+        &mut self,
+        board: &Board,
+        side: Side,
+        valid_moves: &[Coordinate],
+    ) -> Option<Coordinate> {
+        let flips = board.legal_moves_map(side.to_disk());
+        let mut best = None;
+        let mut best_flips = 0;
+        for &coord in valid_moves {
+            let count = flips.get(&coord).copied().unwrap_or(0);
+            if best.is_none() || count > best_flips {
+                best = Some(coord);
+                best_flips = count;
+            }
+        }
+
+        best
+    }
+}
+
+// ---------------------------------------------------------------------
+
+/// Searches `depth` plies ahead with the alpha-beta search.
+pub struct AiStrategy(pub u8);
+
+impl Strategy for AiStrategy {
+    fn choose_move(
+        &mut self,
+        board: &Board,
+        side: Side,
+        valid_moves: &[Coordinate],
+    ) -> Option<Coordinate> {
+        if valid_moves.is_empty() {
+            return None;
+        }
+
+        search::best_move(board, side.to_disk(), self.0)
+    }
+}
+
+/// Plays the moves of an opening book while the game follows it, then
+/// searches `depth` plies ahead like `AiStrategy`.
+pub struct BookStrategy {
+    book: Arc<OpeningBook>,
+    depth: u8,
+}
+
+impl BookStrategy {
+    pub fn new(book: Arc<OpeningBook>, depth: u8) -> BookStrategy {
+        BookStrategy { book, depth }
+    }
+}
+
+impl Strategy for BookStrategy {
+    fn choose_move(
+        &mut self,
+        board: &Board,
+        side: Side,
+        valid_moves: &[Coordinate],
+    ) -> Option<Coordinate> {
+        if valid_moves.is_empty() {
+            return None;
+        }
+
+        let disk = side.to_disk();
+        search::best_move_with_book(board, disk, self.depth, &self.book)
+    }
+}
+
+// =====================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_strategy, AiStrategy, GreedyStrategy};
+    use super::{RandomStrategy, Strategy};
+    use crate::board::{Board, Disk};
+    use crate::engine::Side;
+    use crate::position::Coordinate;
+    use crate::search;
+
+    #[test]
+    fn strategies_choose_valid_moves() {
+        let mut board = Board::new();
+        board.init();
+        let moves = board.valid_moves(Disk::Black);
+
+        let mut random = RandomStrategy::with_seed(7);
+        for _ in 0..10 {
+            let coord = random.choose_move(&board, Side::Dark, &moves);
+            assert!(moves.contains(&coord.unwrap()));
+        }
+        assert_eq!(random.choose_move(&board, Side::Dark, &[]), None);
+
+        let mut ai = AiStrategy(2);
+        let coord = ai.choose_move(&board, Side::Dark, &moves);
+        assert_eq!(coord, search::best_move(&board, Disk::Black, 2));
+        assert_eq!(ai.choose_move(&board, Side::Dark, &[]), None);
+    }
+
+    #[test]
+    fn greedy_strategy_takes_most_flips() {
+        let board: Board = "\
+.ox..... ........ ........ xooo.... ........ ........ ........ ........"
+            .parse()
+            .unwrap();
+        let moves = board.valid_moves(Disk::Black);
+        let coord = GreedyStrategy.choose_move(&board, Side::Dark, &moves);
+        assert_eq!(coord, Some(Coordinate::new('e', 4)));
+        assert_eq!(GreedyStrategy.choose_move(&board, Side::Dark, &[]), None);
+    }
+
+    #[test]
+    fn parse_strategy_names() {
+        assert!(parse_strategy("human", None).unwrap().is_human());
+        assert!(!parse_strategy("random", None).unwrap().is_human());
+        assert!(!parse_strategy("greedy", None).unwrap().is_human());
+        assert!(!parse_strategy("ai:4", None).unwrap().is_human());
+        assert!(parse_strategy("ai", None).is_none());
+        assert!(parse_strategy("ai:0", None).is_none());
+        assert!(parse_strategy("ai:x", None).is_none());
+        assert!(parse_strategy("robot", None).is_none());
+    }
+}
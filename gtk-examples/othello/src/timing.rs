@@ -0,0 +1,187 @@
+use crate::engine::Side;
+use crate::position::Coordinate;
+use std::time::{Duration, Instant};
+
+/// Tells the time for `MoveTimer`, so that tests can use a fake clock.
+pub trait Clock {
+    /// Returns the time passed since a point that stays the same from
+    /// call to call.
+    fn now(&self) -> Duration;
+}
+
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> SystemClock {
+        SystemClock {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> SystemClock {
+        SystemClock::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+// ---------------------------------------------------------------------
+
+/// A move timed from when its player was prompted until it was played.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TimedMove {
+    pub ply: usize,
+    pub side: Side,
+    pub coord: Coordinate,
+    pub time: Duration,
+}
+
+/// Times the moves played at the prompt and sums them up by side.
+pub struct MoveTimer {
+    clock: Box<dyn Clock>,
+    started: Option<Duration>,
+    moves: Vec<TimedMove>,
+}
+
+impl MoveTimer {
+    pub fn new(clock: Box<dyn Clock>) -> MoveTimer {
+        MoveTimer {
+            clock,
+            started: None,
+            moves: Vec::new(),
+        }
+    }
+
+    /// Starts timing the next move, unless the clock is already running
+    /// because the player did something else at the last prompt.
+    pub fn start(&mut self) {
+        if self.started.is_none() {
+            self.started = Some(self.clock.now());
+        }
+    }
+
+    /// Stops the clock for the move played at `ply`, forgetting any
+    /// moves timed from there on before an undo. Returns `None` when the
+    /// clock wasn't running.
+    pub fn record(
+        &mut self,
+        ply: usize,
+        side: Side,
+        coord: Coordinate,
+    ) -> Option<Duration> {
+        let started = self.started.take()?;
+        let time = self.clock.now() - started;
+        self.truncate(ply);
+        self.moves.push(TimedMove {
+            ply,
+            side,
+            coord,
+            time,
+        });
+
+        Some(time)
+    }
+
+    /// Forgets the moves timed at `plies` or later, such as after a new
+    /// game starts.
+    pub fn truncate(&mut self, plies: usize) {
+        self.moves.retain(|timed| timed.ply < plies);
+    }
+
+    /// Returns the moves timed before `plies`, in the order played.
+    pub fn moves(&self, plies: usize) -> Vec<TimedMove> {
+        self.moves
+            .iter()
+            .copied()
+            .filter(|timed| timed.ply < plies)
+            .collect()
+    }
+
+    /// Returns the time `side` spent on its moves before `plies`.
+    pub fn total(&self, side: Side, plies: usize) -> Duration {
+        self.moves(plies)
+            .iter()
+            .filter(|timed| timed.side == side)
+            .map(|timed| timed.time)
+            .sum()
+    }
+}
+
+/// Formats a short time in seconds, such as `4.2s`.
+pub fn format_seconds(time: Duration) -> String {
+    format!("{:.1}s", time.as_secs_f64())
+}
+
+/// Formats a total in minutes and seconds, such as `1:32`.
+pub fn format_minutes(time: Duration) -> String {
+    let seconds = time.as_secs();
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+// =====================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{format_minutes, format_seconds, Clock, MoveTimer};
+    use crate::engine::Side;
+    use crate::position::Coordinate;
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use std::time::Duration;
+
+    struct FakeClock(Rc<Cell<Duration>>);
+
+    impl Clock for FakeClock {
+        fn now(&self) -> Duration {
+            self.0.get()
+        }
+    }
+
+    #[test]
+    fn move_timer_totals() {
+        let now = Rc::new(Cell::new(Duration::from_secs(10)));
+        let mut timer = MoveTimer::new(Box::new(FakeClock(Rc::clone(&now))));
+        let f5 = Coordinate::new('f', 5);
+        assert_eq!(timer.record(0, Side::Dark, f5), None);
+
+        timer.start();
+        now.set(Duration::from_millis(12_500));
+        timer.start();
+        now.set(Duration::from_secs(14));
+        let time = timer.record(0, Side::Dark, f5);
+        assert_eq!(time, Some(Duration::from_secs(4)));
+
+        timer.start();
+        now.set(Duration::from_secs(20));
+        timer.record(1, Side::Light, Coordinate::new('d', 6));
+        timer.start();
+        now.set(Duration::from_secs(21));
+        timer.record(2, Side::Dark, Coordinate::new('c', 3));
+        assert_eq!(timer.total(Side::Dark, 3), Duration::from_secs(5));
+        assert_eq!(timer.total(Side::Light, 3), Duration::from_secs(6));
+        assert_eq!(timer.total(Side::Dark, 2), Duration::from_secs(4));
+
+        timer.start();
+        now.set(Duration::from_secs(30));
+        timer.record(1, Side::Light, Coordinate::new('f', 6));
+        assert_eq!(timer.moves(60).len(), 2);
+        assert_eq!(timer.total(Side::Light, 60), Duration::from_secs(9));
+    }
+
+    #[test]
+    fn format_times() {
+        assert_eq!(format_seconds(Duration::from_millis(4_240)), "4.2s");
+        assert_eq!(format_seconds(Duration::from_secs(0)), "0.0s");
+        assert_eq!(format_minutes(Duration::from_secs(92)), "1:32");
+        assert_eq!(format_minutes(Duration::from_millis(9_900)), "0:09");
+        assert_eq!(format_minutes(Duration::from_secs(3_600)), "60:00");
+    }
+}